@@ -0,0 +1,301 @@
+use bevy::prelude::*;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use wasmtime::component::Component;
+use wasmtime::Engine;
+
+/// Cached components kept around, the least recently used ones are removed beyond this
+pub const MAX_CACHE_ENTRIES: usize = 64;
+
+/// On-disk cache of natively compiled script components.
+/// Entries are keyed by the module bytes and the engine compatibility hash, which covers the
+/// wasmtime version and the engine configuration, so stale entries are simply never hit.
+///
+/// Cached entries are loaded as native code, so the directory must only be writable by the
+/// current user, never a shared location like the system temp directory. Entries are only
+/// loaded from a directory owned by the current user with mode 0700.
+#[derive(Resource, Clone)]
+pub struct ComponentCache {
+    pub directory: PathBuf,
+}
+
+impl ComponentCache {
+    pub fn new(directory: impl Into<PathBuf>) -> Self {
+        Self {
+            directory: directory.into(),
+        }
+    }
+
+    pub fn load_or_compile(
+        &self,
+        engine: &Engine,
+        wasm_module_bytes: &[u8],
+    ) -> wasmtime::Result<Component> {
+        let cache_file = self
+            .directory
+            .join(format!("{}.cwasm", cache_key(engine, wasm_module_bytes)));
+
+        let serialized = if is_private_dir(&self.directory) {
+            fs::read(&cache_file).ok()
+        } else {
+            if self.directory.exists() {
+                warn!(
+                    "ignoring component cache {:?}, it is not private to the current user",
+                    self.directory
+                );
+            }
+
+            None
+        };
+
+        if let Some(serialized) = serialized {
+            // SAFETY: the cache directory was just checked to be private to the user, so it only
+            // holds output of `Component::serialize`. The key covers the engine compatibility
+            // hash, so the artifact was produced by the same wasmtime for an equivalent engine
+            match unsafe { Component::deserialize(engine, &serialized) } {
+                Ok(component) => {
+                    mark_used(&cache_file);
+                    return Ok(component);
+                }
+                Err(e) => warn!("discarding unusable cached component {cache_file:?}: {e:?}"),
+            }
+        }
+
+        let component = compile_component(engine, wasm_module_bytes)?;
+
+        let written = create_private_dir(&self.directory)
+            .map_err(wasmtime::Error::from)
+            .and_then(|_| component.serialize())
+            .and_then(|serialized| Ok(fs::write(&cache_file, serialized)?));
+
+        match written {
+            Ok(()) => self.evict(MAX_CACHE_ENTRIES),
+            Err(e) => warn!("failed to write component cache entry {cache_file:?}: {e:?}"),
+        }
+
+        Ok(component)
+    }
+
+    /// Removes the least recently used entries until at most `keep` are left
+    pub fn evict(&self, keep: usize) {
+        let Ok(entries) = fs::read_dir(&self.directory) else {
+            return;
+        };
+
+        let mut cached: Vec<(SystemTime, PathBuf)> = entries
+            .filter_map(Result::ok)
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "cwasm"))
+            .map(|path| {
+                let used_at = fs::metadata(&path)
+                    .and_then(|metadata| metadata.modified())
+                    .unwrap_or(SystemTime::UNIX_EPOCH);
+
+                (used_at, path)
+            })
+            .collect();
+
+        if cached.len() <= keep {
+            return;
+        }
+
+        // Most recently used first
+        cached.sort_by(|a, b| b.0.cmp(&a.0));
+
+        for (_, path) in cached.into_iter().skip(keep) {
+            if let Err(e) = fs::remove_file(&path) {
+                warn!("failed to evict component cache entry {path:?}: {e:?}");
+            }
+        }
+    }
+}
+
+/// Encodes a core wasm module as a component and compiles it to native code
+pub fn compile_component(engine: &Engine, wasm_module_bytes: &[u8]) -> wasmtime::Result<Component> {
+    let component_bytes = wit_component::ComponentEncoder::default()
+        .module(wasm_module_bytes)?
+        .encode()?;
+
+    Component::from_binary(engine, &component_bytes)
+}
+
+/// Hex digest that stays the same across builds and launches, unlike `DefaultHasher`
+fn cache_key(engine: &Engine, wasm_module_bytes: &[u8]) -> String {
+    let mut hasher = StableHasher(blake3::Hasher::new());
+
+    engine.precompile_compatibility_hash().hash(&mut hasher);
+    hasher.0.update(wasm_module_bytes);
+
+    hasher.0.finalize().to_hex().to_string()
+}
+
+/// Feeds `Hash` implementations into blake3
+struct StableHasher(blake3::Hasher);
+
+impl Hasher for StableHasher {
+    fn finish(&self) -> u64 {
+        let digest = self.0.finalize();
+
+        u64::from_le_bytes(digest.as_bytes()[..8].try_into().unwrap())
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        self.0.update(bytes);
+    }
+}
+
+/// Bumps the modification time so eviction sees the entry as recently used
+fn mark_used(path: &Path) {
+    let touched = fs::File::options()
+        .write(true)
+        .open(path)
+        .and_then(|file| file.set_modified(SystemTime::now()));
+
+    if let Err(e) = touched {
+        warn!("failed to mark component cache entry {path:?} as used: {e:?}");
+    }
+}
+
+/// Whether the directory is owned by the current user and closed to everyone else
+fn is_private_dir(directory: &Path) -> bool {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+
+        // SAFETY: geteuid has no preconditions and cannot fail
+        let user = unsafe { libc::geteuid() };
+
+        fs::symlink_metadata(directory).is_ok_and(|metadata| {
+            metadata.is_dir() && metadata.uid() == user && metadata.mode() & 0o777 == 0o700
+        })
+    }
+
+    #[cfg(not(unix))]
+    {
+        fs::symlink_metadata(directory).is_ok_and(|metadata| metadata.is_dir())
+    }
+}
+
+fn create_private_dir(directory: &Path) -> std::io::Result<()> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::{DirBuilderExt, PermissionsExt};
+
+        if is_private_dir(directory) {
+            return Ok(());
+        }
+
+        fs::DirBuilder::new()
+            .recursive(true)
+            .mode(0o700)
+            .create(directory)?;
+
+        // Tighten directories left behind by older builds. Others may have written to them,
+        // so their entries can't be trusted
+        fs::set_permissions(directory, fs::Permissions::from_mode(0o700))?;
+
+        for entry in fs::read_dir(directory)? {
+            let path = entry?.path();
+
+            if path.extension().is_some_and(|ext| ext == "cwasm") {
+                fs::remove_file(path)?;
+            }
+        }
+
+        if is_private_dir(directory) {
+            Ok(())
+        } else {
+            Err(std::io::Error::new(
+                std::io::ErrorKind::PermissionDenied,
+                format!("{directory:?} is not owned by the current user"),
+            ))
+        }
+    }
+
+    #[cfg(not(unix))]
+    {
+        fs::create_dir_all(directory)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasmtime::Config;
+
+    fn engine() -> Engine {
+        Engine::new(Config::new().wasm_component_model(true).consume_fuel(true)).unwrap()
+    }
+
+    #[test]
+    fn cache_key_is_stable_and_covers_the_module() {
+        let engine = engine();
+
+        assert_eq!(cache_key(&engine, b"module"), cache_key(&engine, b"module"));
+        assert_ne!(cache_key(&engine, b"module"), cache_key(&engine, b"other"));
+    }
+
+    #[test]
+    fn cache_key_covers_the_engine_configuration() {
+        let other_engine =
+            Engine::new(Config::new().wasm_component_model(true).consume_fuel(false)).unwrap();
+
+        assert_ne!(
+            cache_key(&engine(), b"module"),
+            cache_key(&other_engine, b"module")
+        );
+    }
+
+    #[test]
+    fn eviction_keeps_the_most_recently_used_entries() {
+        let directory =
+            std::env::temp_dir().join(format!("component-cache-eviction-{}", std::process::id()));
+        create_private_dir(&directory).unwrap();
+
+        let cache = ComponentCache::new(&directory);
+        let now = SystemTime::now();
+
+        for (index, name) in ["old", "middle", "new"].iter().enumerate() {
+            let path = directory.join(format!("{name}.cwasm"));
+            fs::write(&path, []).unwrap();
+
+            fs::File::options()
+                .write(true)
+                .open(&path)
+                .unwrap()
+                .set_modified(now - std::time::Duration::from_secs(60 * (3 - index as u64)))
+                .unwrap();
+        }
+
+        cache.evict(2);
+
+        assert!(!directory.join("old.cwasm").exists());
+        assert!(directory.join("middle.cwasm").exists());
+        assert!(directory.join("new.cwasm").exists());
+
+        fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn directories_open_to_others_are_tightened_and_emptied() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let directory =
+            std::env::temp_dir().join(format!("component-cache-private-{}", std::process::id()));
+        fs::create_dir_all(&directory).unwrap();
+        fs::set_permissions(&directory, fs::Permissions::from_mode(0o777)).unwrap();
+        fs::write(directory.join("planted.cwasm"), []).unwrap();
+
+        assert!(!is_private_dir(&directory));
+
+        create_private_dir(&directory).unwrap();
+
+        assert!(is_private_dir(&directory));
+        assert!(!directory.join("planted.cwasm").exists());
+
+        fs::remove_dir_all(&directory).unwrap();
+    }
+}
@@ -1,16 +1,48 @@
-use crate::scripted_entity::WasmEngine;
+use crate::component_cache::ComponentCache;
+use crate::scripted_entity::{ScriptFuelBudget, WasmEngine};
+use crate::wasm_script_asset::CompiledComponents;
 use bevy::app::{App, Plugin};
 use wasmtime::{Config, Engine};
 
+pub mod component_cache;
 pub mod scripted_entity;
 pub mod wasm_script_asset;
 
-pub struct WasmtimeScriptPlugin;
+/// Roughly one unit of fuel is consumed per executed wasm instruction
+pub const DEFAULT_FUEL_PER_CALL: u64 = 10_000_000;
+
+pub struct WasmtimeScriptPlugin {
+    /// Fuel handed to a script before every guest call, unless its prototype overrides it
+    pub fuel_per_call: u64,
+    /// Where compiled components are cached between launches, `None` compiles every launch.
+    /// Off by default, the embedding game picks a directory private to the user
+    pub component_cache: Option<ComponentCache>,
+}
+
+impl Default for WasmtimeScriptPlugin {
+    fn default() -> Self {
+        Self {
+            fuel_per_call: DEFAULT_FUEL_PER_CALL,
+            component_cache: None,
+        }
+    }
+}
 
 impl Plugin for WasmtimeScriptPlugin {
     fn build(&self, app: &mut App) {
         app.insert_resource(WasmEngine(
-            Engine::new(Config::new().wasm_component_model(true)).unwrap(),
-        ));
+            Engine::new(
+                Config::new()
+                    .wasm_component_model(true)
+                    .consume_fuel(true),
+            )
+            .unwrap(),
+        ))
+        .insert_resource(ScriptFuelBudget(self.fuel_per_call))
+        .init_resource::<CompiledComponents>();
+
+        if let Some(cache) = &self.component_cache {
+            app.insert_resource(cache.clone());
+        }
     }
 }
@@ -3,3 +3,7 @@ use wasmtime::Engine;
 
 #[derive(Resource)]
 pub struct WasmEngine(pub Engine);
+
+/// Default amount of fuel a script gets for each call into the guest
+#[derive(Resource, Clone, Copy)]
+pub struct ScriptFuelBudget(pub u64);
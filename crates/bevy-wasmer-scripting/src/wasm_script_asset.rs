@@ -1,14 +1,74 @@
+use crate::component_cache::{compile_component, ComponentCache};
 use bevy::asset::io::Reader;
 use bevy::asset::{AssetLoader, LoadContext};
 use bevy::prelude::*;
+use bevy::utils::HashMap;
 use std::error::Error;
+use wasmtime::component::Component;
+use wasmtime::Engine;
 
 /// Represents compiled wasm code for a script module
 #[derive(Asset, TypePath)]
 pub struct WasmScriptModuleBytes {
     pub wasm_module_bytes: Vec<u8>,
-    pub aot_component_bytes: Option<Vec<u8>>,
-    pub aot_component: Option< wasmtime::component::Component>,
+    /// Hash of `wasm_module_bytes`, tells real changes apart from the asset merely being touched
+    pub module_hash: blake3::Hash,
+}
+
+/// Components compiled from the loaded script modules. Kept apart from the assets, as borrowing
+/// an asset mutably reports it as modified.
+#[derive(Resource, Default)]
+pub struct CompiledComponents {
+    components: HashMap<AssetId<WasmScriptModuleBytes>, CompiledComponent>,
+}
+
+struct CompiledComponent {
+    module_hash: blake3::Hash,
+    component: Component,
+}
+
+impl CompiledComponents {
+    /// The component of a module, compiled again only when the module bytes changed.
+    /// Goes through the on-disk cache when one is given.
+    pub fn get_or_compile(
+        &mut self,
+        id: AssetId<WasmScriptModuleBytes>,
+        module: &WasmScriptModuleBytes,
+        engine: &Engine,
+        cache: Option<&ComponentCache>,
+    ) -> wasmtime::Result<Component> {
+        if let Some(compiled) = self.components.get(&id) {
+            if compiled.module_hash == module.module_hash {
+                return Ok(compiled.component.clone());
+            }
+        }
+
+        let component = match cache {
+            Some(cache) => cache.load_or_compile(engine, &module.wasm_module_bytes)?,
+            None => compile_component(engine, &module.wasm_module_bytes)?,
+        };
+
+        self.components.insert(
+            id,
+            CompiledComponent {
+                module_hash: module.module_hash,
+                component: component.clone(),
+            },
+        );
+
+        Ok(component)
+    }
+
+    /// Whether the module changed since its component got compiled, or was never compiled
+    pub fn is_outdated(
+        &self,
+        id: AssetId<WasmScriptModuleBytes>,
+        module: &WasmScriptModuleBytes,
+    ) -> bool {
+        self.components
+            .get(&id)
+            .is_none_or(|compiled| compiled.module_hash != module.module_hash)
+    }
 }
 
 #[derive(Default)]
@@ -29,9 +89,8 @@ impl AssetLoader for WasmScriptModuleBytesLoader {
         reader.read_to_end(&mut wasm_module_bytes).await?;
 
         Ok(WasmScriptModuleBytes {
+            module_hash: blake3::hash(&wasm_module_bytes),
             wasm_module_bytes,
-            aot_component_bytes: None,
-            aot_component: None,
         })
     }
 
@@ -39,3 +98,35 @@ impl AssetLoader for WasmScriptModuleBytesLoader {
         &["wasm"]
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasmtime::Config;
+
+    /// `(module)`, the smallest valid core module
+    const EMPTY_MODULE: &[u8] = b"\0asm\x01\0\0\0";
+
+    fn module(wasm_module_bytes: &[u8]) -> WasmScriptModuleBytes {
+        WasmScriptModuleBytes {
+            module_hash: blake3::hash(wasm_module_bytes),
+            wasm_module_bytes: wasm_module_bytes.to_vec(),
+        }
+    }
+
+    #[test]
+    fn components_are_only_outdated_when_the_module_bytes_change() {
+        let engine = Engine::new(Config::new().wasm_component_model(true)).unwrap();
+        let id = AssetId::<WasmScriptModuleBytes>::default();
+        let mut compiled_components = CompiledComponents::default();
+
+        assert!(compiled_components.is_outdated(id, &module(EMPTY_MODULE)));
+
+        compiled_components
+            .get_or_compile(id, &module(EMPTY_MODULE), &engine, None)
+            .unwrap();
+
+        assert!(!compiled_components.is_outdated(id, &module(EMPTY_MODULE)));
+        assert!(compiled_components.is_outdated(id, &module(b"changed")));
+    }
+}
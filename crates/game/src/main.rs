@@ -3,12 +3,14 @@ mod systems;
 use crate::systems::init_game::SimplePlatformGame;
 use bevy::prelude::*;
 use bevy::window::WindowMode;
+use gamejam_platform_controller::input_systems::input_replay::InputReplayMode;
 use simple_2d_camera::SimplePixel2dCameraPlugin;
 
 fn main() {
     let mut app = App::new();
 
     app.insert_resource(ClearColor(Color::srgb(0.1, 0.1, 0.7)))
+        .insert_resource(InputReplayMode::from_args(std::env::args()))
         .add_plugins((
             DefaultPlugins
                 .set(ImagePlugin::default_nearest())
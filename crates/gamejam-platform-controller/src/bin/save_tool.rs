@@ -0,0 +1,84 @@
+//! Inspects save files outside the game.
+//!
+//! ```text
+//! save_tool dump <slot or path>
+//! save_tool validate <slot or path>...
+//! ```
+//!
+//! Saves are migrated to the current version before being printed or checked,
+//! the file on disk is left untouched.
+
+use anyhow::bail;
+use gamejam_platform_controller::save_game::save_file::{
+    save_directory, slot_path, SaveFile, SAVE_FILE_VERSION,
+};
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    let result = match args.split_first() {
+        Some((command, saves)) if command == "dump" && saves.len() == 1 => dump(&saves[0]),
+        Some((command, saves)) if command == "validate" && !saves.is_empty() => validate(saves),
+        _ => {
+            eprintln!("usage: save_tool dump <slot or path>");
+            eprintln!("       save_tool validate <slot or path>...");
+            eprintln!("slots are read from {}", save_directory().display());
+            return ExitCode::FAILURE;
+        }
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{e:?}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// A bare number picks a slot in the save directory, anything else is a path
+fn save_path(save: &str) -> PathBuf {
+    save.parse()
+        .map(slot_path)
+        .unwrap_or_else(|_| PathBuf::from(save))
+}
+
+fn dump(save: &str) -> anyhow::Result<()> {
+    let (save, _) = SaveFile::read_path(&save_path(save))?;
+
+    println!("{}", serde_json::to_string_pretty(&save)?);
+
+    Ok(())
+}
+
+fn validate(saves: &[String]) -> anyhow::Result<()> {
+    let mut invalid = 0;
+
+    for save in saves {
+        let path = save_path(save);
+
+        match SaveFile::read_path(&path) {
+            Ok((_, version)) if version == SAVE_FILE_VERSION => {
+                println!("{}: ok, version {version}", path.display());
+            }
+            Ok((_, version)) => {
+                println!(
+                    "{}: ok, migrates from version {version} to {SAVE_FILE_VERSION}",
+                    path.display()
+                );
+            }
+            Err(e) => {
+                println!("{}: invalid, {e:#}", path.display());
+                invalid += 1;
+            }
+        }
+    }
+
+    if invalid > 0 {
+        bail!("{invalid} of {} saves are invalid", saves.len());
+    }
+
+    Ok(())
+}
@@ -1,11 +1,17 @@
-use crate::combat::combat_components::{Health, Invulnerable};
-use crate::movement_systems::movement_components::ApplyTimedLinearVelocity;
+use crate::audio::audio_components::AudioEffect;
+use crate::combat::combat_components::{
+    DamageType, Health, HitInvulnerability, HitInvulnerable, Invulnerable, Resistances, Stamina,
+};
+use crate::combat::knockback::{apply_knockback, KnockbackProfile, WeightClass};
+use crate::combat::status_effects::{apply_status_effect, StatusEffect};
+use crate::graphics::flicker_system::Flickering;
+use crate::player_const_rules::PLAYER_PARRY_STAMINA_COST;
 use crate::scripting::scripted_game_entity::EntityScript;
-use bevy::math::vec2;
+use crate::timing::timer_system::add_timed_component_to_entity;
+use crate::timing::timing_component::TimerComponent;
 use bevy::prelude::*;
-use std::time::Duration;
 use bevy_trauma_shake::Shake;
-use crate::player_systems::player_components::Player;
+use crate::player_systems::player_components::{Parrying, Player};
 
 /// An attackable entity (reacts to attacks)
 #[derive(Component, Default, Reflect)]
@@ -14,31 +20,50 @@ pub struct Attackable;
 /// Attached when an attackable gets hit by an attack
 #[derive(Component, Default, Reflect)]
 pub struct Attacked {
+    /// Entity the attack came from, told when the attack gets parried
+    pub attacker: Option<Entity>,
     pub damage: u32,
     pub origin: Vec2,
     pub vector: Vec2,
-    pub force: f32,
+    pub knockback: KnockbackProfile,
+    pub damage_type: DamageType,
+    /// Applied to the attacked entity unless the attack gets parried
+    pub effects: Vec<StatusEffect>,
 }
 
 pub struct AttackablePlugin;
 
 impl Plugin for AttackablePlugin {
     fn build(&self, app: &mut App) {
-        app.add_observer(attackable_attacked_observer);
+        app.add_observer(attackable_attacked_observer)
+            .add_observer(attack_parried_observer);
     }
 }
 
+/// Triggered on a parrying entity when an attack lands on it
+#[derive(Event)]
+pub struct AttackParried {
+    pub attacker: Option<Entity>,
+}
+
 pub fn attackable_attacked_observer(
     trigger: Trigger<OnAdd, Attacked>,
     mut commands: Commands,
+    time: Res<Time>,
     mut attackables: Query<
         (
             Entity,
             &Attackable,
             &Attacked,
             Option<&mut Health>,
+            Option<&Resistances>,
             Option<&mut EntityScript>,
-            Option<&Player>
+            Option<&Player>,
+            Has<Parrying>,
+            Has<HitInvulnerable>,
+            Option<&mut HitInvulnerability>,
+            Option<&mut TimerComponent>,
+            Option<&WeightClass>,
         ),
         Without<Invulnerable>,
     >,
@@ -48,27 +73,77 @@ pub fn attackable_attacked_observer(
 
     commands.entity(trigger.entity()).remove::<Attacked>();
 
-    for (entity, _attackable, attack, hp, script, player) in attackables.iter_mut() {
+    for (
+        entity,
+        _attackable,
+        attack,
+        hp,
+        resistances,
+        script,
+        player,
+        parrying,
+        hit_invulnerable,
+        hit_invulnerability,
+        timer,
+        weight_class,
+    ) in attackables.iter_mut()
+    {
         if entity != trigger.entity() {
             continue;
         }
 
+        if parrying {
+            commands.trigger_targets(
+                AttackParried {
+                    attacker: attack.attacker,
+                },
+                entity,
+            );
+
+            continue;
+        }
+
+        if hit_invulnerable {
+            continue;
+        }
+
+        if let Some(mut hit_invulnerability) = hit_invulnerability {
+            if let Some(attacker) = attack.attacker {
+                if !hit_invulnerability.try_hit(attacker, time.elapsed_secs_f64()) {
+                    continue;
+                }
+            }
+
+            let duration = hit_invulnerability.duration();
+
+            if let Some(mut timer) = timer.filter(|_| !duration.is_zero()) {
+                add_timed_component_to_entity(
+                    &mut commands.entity(entity),
+                    &mut timer,
+                    (HitInvulnerable, Flickering),
+                    duration,
+                );
+            }
+        }
+
         if let Some(mut hp) = hp {
-            hp.0.consume(attack.damage);
+            let damage = resistances.map_or(attack.damage, |resistances| {
+                resistances.resist(attack.damage_type, attack.damage)
+            });
+
+            hp.0.consume(damage);
         }
 
-        let pushback_time = 0.1 + attack.force * 0.03;
-        let push_direction = attack.vector;
+        for effect in attack.effects.iter() {
+            apply_status_effect(&mut commands, entity, *effect);
+        }
 
-        commands.entity(entity).insert(ApplyTimedLinearVelocity {
-            timer: Timer::new(Duration::from_secs_f32(pushback_time), TimerMode::Once),
-            acceleration_function: Box::new(move |remaining_time: f32| {
-                let dir = vec2(push_direction.normalize().x, 1.2).normalize()
-                    * (remaining_time / pushback_time)
-                    * 3000.;
-                dir
-            }),
-        });
+        apply_knockback(
+            &mut commands.entity(entity),
+            attack.knockback,
+            attack.vector,
+            weight_class.copied().unwrap_or_default(),
+        );
 
         // Shake the camera if the player is attacked
         if player.is_some() {
@@ -80,3 +155,37 @@ pub fn attackable_attacked_observer(
         }
     }
 }
+
+/// Refunds the parry once per activation and staggers the attacker
+pub fn attack_parried_observer(
+    trigger: Trigger<AttackParried>,
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut parrying: Query<(&mut Stamina, &mut Parrying)>,
+    mut scripts: Query<&mut EntityScript>,
+    mut camera_shake: Query<&mut Shake, With<Camera2d>>,
+) {
+    if let Ok((mut stamina, mut parrying)) = parrying.get_mut(trigger.entity()) {
+        if !parrying.stamina_refunded {
+            parrying.stamina_refunded = true;
+            stamina.0.restore(PLAYER_PARRY_STAMINA_COST);
+        }
+    }
+
+    if let Some(mut script) = trigger
+        .attacker
+        .and_then(|attacker| scripts.get_mut(attacker).ok())
+    {
+        script.parried();
+    }
+
+    if let Ok(mut camera_shake) = camera_shake.get_single_mut() {
+        camera_shake.add_trauma(0.15);
+    }
+
+    commands.spawn((
+        AudioPlayer::new(asset_server.load("audio/metal-small2.wav")),
+        AudioEffect,
+        PlaybackSettings::ONCE,
+    ));
+}
@@ -1,23 +1,111 @@
 use bevy::math::Vec2;
-use bevy::prelude::{Component, Entity, Resource};
+use bevy::prelude::{Component, Entity, Reflect, Resource};
+use bevy::utils::HashMap;
 use bevy::time::{Timer, TimerMode};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::time::Duration;
 use haalka::prelude::Mutable;
+use crate::combat::knockback::KnockbackProfile;
+use crate::combat::status_effects::StatusEffect;
+use crate::player_const_rules::{PLAYER_HIT_ATTACKER_COOLDOWN, PLAYER_HIT_INVULNERABILITY};
 use crate::player_systems::player_components::StatBarMutables;
 
+/// An attack scheduled by a script, a ray from `origin` along `vector`
 #[derive(Component, Debug)]
 pub struct ScheduledAttack {
     pub attacker: Entity,
     pub origin: Vec2,
     pub vector: Vec2,
     pub delay: Timer,
-    pub force: f32,
+    pub knockback: KnockbackProfile,
     pub damage: u32,
+    pub damage_type: DamageType,
+    /// Applied to everything the attack hits
+    pub effects: Vec<StatusEffect>,
+}
+
+/// Kind of damage an attack deals, each is resisted separately
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, Reflect,
+)]
+#[serde(rename_all = "kebab-case")]
+pub enum DamageType {
+    #[default]
+    Physical,
+    Fire,
+    Poison,
+    Frost,
+}
+
+/// Fraction of each damage type an entity shrugs off.
+/// 1 makes it immune, negative values make it take extra damage.
+#[derive(Component, Default, Clone, Debug, Serialize, Deserialize)]
+pub struct Resistances(pub BTreeMap<DamageType, f32>);
+
+impl Resistances {
+    /// Multiplier applied to damage of the type
+    pub fn damage_factor(&self, damage_type: DamageType) -> f32 {
+        (1. - self.0.get(&damage_type).copied().unwrap_or(0.)).max(0.)
+    }
+
+    pub fn resist(&self, damage_type: DamageType, damage: u32) -> u32 {
+        (damage as f32 * self.damage_factor(damage_type)).round() as u32
+    }
 }
 
 #[derive(Component)]
 pub struct Invulnerable;
 
+/// Attached for a while after taking a hit, kept apart from `Invulnerable` so rolls don't cut it short
+#[derive(Component)]
+pub struct HitInvulnerable;
+
+/// How long an entity can't be hit after taking a hit, and how long before the same attacker
+/// can hit it again, so attacks landing several times in a row only count once
+#[derive(Component, Clone, Debug, Serialize, Deserialize)]
+pub struct HitInvulnerability {
+    pub duration_millis: u64,
+    #[serde(default)]
+    pub attacker_cooldown_millis: u64,
+    /// Elapsed seconds of the last hit of every attacker
+    #[serde(skip)]
+    last_hit_at: HashMap<Entity, f64>,
+}
+
+impl HitInvulnerability {
+    pub fn new(duration_millis: u64, attacker_cooldown_millis: u64) -> Self {
+        Self {
+            duration_millis,
+            attacker_cooldown_millis,
+            last_hit_at: HashMap::default(),
+        }
+    }
+
+    pub fn default_player() -> Self {
+        Self::new(PLAYER_HIT_INVULNERABILITY, PLAYER_HIT_ATTACKER_COOLDOWN)
+    }
+
+    pub fn duration(&self) -> Duration {
+        Duration::from_millis(self.duration_millis)
+    }
+
+    /// Records a hit by the attacker, false while it is still on cooldown
+    pub fn try_hit(&mut self, attacker: Entity, now: f64) -> bool {
+        let cooldown = Duration::from_millis(self.attacker_cooldown_millis).as_secs_f64();
+
+        self.last_hit_at.retain(|_, hit_at| now - *hit_at < cooldown);
+
+        if self.last_hit_at.contains_key(&attacker) {
+            return false;
+        }
+
+        self.last_hit_at.insert(attacker, now);
+
+        true
+    }
+}
+
 #[derive(Component)]
 pub struct Boss;
 
@@ -61,6 +149,11 @@ impl Stat {
         self.current -= amount;
         self.newly_consumed = amount;
     }
+
+    pub fn restore(&mut self, amount: u32) {
+        self.current = self.max.min(self.current + amount);
+    }
+
     pub fn try_consume(&mut self, amount: u32) -> bool {
         if self.current < amount {
             return false;
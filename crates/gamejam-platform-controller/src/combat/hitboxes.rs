@@ -0,0 +1,326 @@
+use crate::combat::attackable::{Attackable, Attacked};
+use crate::combat::combat_components::DamageType;
+use crate::combat::knockback::KnockbackProfile;
+use crate::combat::status_effects::StatusEffect;
+use crate::graphics::animation_system::SpriteAnimation;
+use crate::graphics::sprite_collection::AnimationInfo;
+use crate::player_systems::player_components::Player;
+use avian2d::prelude::{Collider, Rotation};
+use bevy::prelude::*;
+use bevy::utils::HashSet;
+use std::time::Duration;
+
+/// Hitbox of the animation frame being shown, relative to the entity
+#[derive(Clone, Debug)]
+pub struct ActiveHitbox {
+    pub rect: Rect,
+    pub damage: u32,
+    pub knockback: KnockbackProfile,
+    pub damage_type: DamageType,
+    pub effects: Vec<StatusEffect>,
+}
+
+/// Hitboxes of the animation frame being shown. An attack lasts for as long as
+/// there are boxes active and the same animation keeps playing, and hits every entity at most once.
+///
+/// Only sprite animations drive these, attacks scheduled by scripts are still
+/// tested as rays by `scheduled_attack_system`.
+#[derive(Component, Default)]
+pub struct ActiveHitboxes {
+    pub boxes: Vec<ActiveHitbox>,
+    hit_entities: HashSet<Entity>,
+    animation_name: String,
+    /// Frame and time into it the animation was at when the boxes were set
+    animation_progress: (u32, Duration),
+}
+
+impl ActiveHitboxes {
+    fn set(&mut self, animation: &SpriteAnimation, boxes: Vec<ActiveHitbox>) {
+        let progress = (animation.animation_frame, animation.timer.elapsed());
+
+        // Boxes showing up after a frame without any, in another animation or in one that
+        // started over, be it restarted or repeating, start a new attack
+        if self.boxes.is_empty()
+            || self.animation_name != animation.animation_name
+            || progress < self.animation_progress
+        {
+            self.hit_entities.clear();
+        }
+
+        if self.animation_name != animation.animation_name {
+            self.animation_name.clone_from(&animation.animation_name);
+        }
+
+        self.animation_progress = progress;
+        self.boxes = boxes;
+    }
+}
+
+/// Hurtboxes of the animation frame being shown, relative to the entity.
+/// Empty when the frame has none, its collider gets hit instead.
+#[derive(Component, Default)]
+pub struct ActiveHurtboxes(pub Vec<Rect>);
+
+/// Triggered on the attacker when one of its hitboxes hits something
+#[derive(Event)]
+pub struct HitLanded {
+    pub target: Entity,
+}
+
+/// Activates the boxes of an animation frame, inserting the components the first time any show up
+pub fn update_frame_boxes(
+    commands: &mut EntityCommands,
+    sprite_animation: &SpriteAnimation,
+    animation: &AnimationInfo,
+    frame: u32,
+    flip_x: bool,
+    hitboxes: Option<Mut<ActiveHitboxes>>,
+    hurtboxes: Option<Mut<ActiveHurtboxes>>,
+) {
+    let frame_hitboxes: Vec<ActiveHitbox> = animation
+        .hitboxes
+        .iter()
+        .filter(|hitbox| hitbox.frames.contains(&frame))
+        .map(|hitbox| ActiveHitbox {
+            rect: hitbox.rect(flip_x),
+            damage: hitbox.damage,
+            knockback: hitbox
+                .knockback
+                .unwrap_or_else(|| KnockbackProfile::from_force(hitbox.force)),
+            damage_type: hitbox.damage_type,
+            effects: hitbox.effects.clone(),
+        })
+        .collect();
+
+    let frame_hurtboxes: Vec<Rect> = animation
+        .hurtboxes
+        .iter()
+        .filter(|hurtbox| hurtbox.frames.contains(&frame))
+        .map(|hurtbox| hurtbox.rect(flip_x))
+        .collect();
+
+    match hitboxes {
+        Some(mut hitboxes) => hitboxes.set(sprite_animation, frame_hitboxes),
+        None if !frame_hitboxes.is_empty() => {
+            let mut hitboxes = ActiveHitboxes::default();
+            hitboxes.set(sprite_animation, frame_hitboxes);
+
+            commands.insert(hitboxes);
+        }
+        None => {}
+    }
+
+    match hurtboxes {
+        Some(mut hurtboxes) => hurtboxes.0 = frame_hurtboxes,
+        None if !frame_hurtboxes.is_empty() => {
+            commands.insert(ActiveHurtboxes(frame_hurtboxes));
+        }
+        None => {}
+    }
+}
+
+/// Hits what the active hitboxes overlap. The player and everything else are on
+/// opposing sides, hitboxes never hit their own side.
+pub fn hitbox_overlap_system(
+    mut commands: Commands,
+    mut attackers: Query<(Entity, &Transform, &mut ActiveHitboxes, Has<Player>)>,
+    targets: Query<
+        (
+            Entity,
+            &Transform,
+            Option<&ActiveHurtboxes>,
+            Option<&Collider>,
+            Has<Player>,
+        ),
+        With<Attackable>,
+    >,
+) {
+    for (attacker, attacker_transform, mut hitboxes, attacker_is_player) in attackers.iter_mut() {
+        if hitboxes.boxes.is_empty() {
+            continue;
+        }
+
+        let attacker_position = attacker_transform.translation.truncate();
+
+        for (target, target_transform, hurtboxes, collider, target_is_player) in targets.iter() {
+            if target_is_player == attacker_is_player || hitboxes.hit_entities.contains(&target) {
+                continue;
+            }
+
+            let target_position = target_transform.translation.truncate();
+            let hurt_rects = hurt_rects(target_position, hurtboxes, collider);
+
+            let Some(hitbox) = hitboxes
+                .boxes
+                .iter()
+                .find(|hitbox| {
+                    let hit_rect = offset_rect(hitbox.rect, attacker_position);
+
+                    hurt_rects
+                        .iter()
+                        .any(|hurt_rect| !hit_rect.intersect(*hurt_rect).is_empty())
+                })
+                .cloned()
+            else {
+                continue;
+            };
+
+            hitboxes.hit_entities.insert(target);
+
+            commands.entity(target).insert(Attacked {
+                attacker: Some(attacker),
+                damage: hitbox.damage,
+                origin: attacker_position,
+                vector: (target_position - attacker_position)
+                    .try_normalize()
+                    .unwrap_or(Vec2::Y),
+                knockback: hitbox.knockback,
+                damage_type: hitbox.damage_type,
+                effects: hitbox.effects.clone(),
+            });
+
+            commands.trigger_targets(HitLanded { target }, attacker);
+        }
+    }
+}
+
+/// World space hurtboxes of a target, its collider bounds when the current frame has none
+fn hurt_rects(
+    position: Vec2,
+    hurtboxes: Option<&ActiveHurtboxes>,
+    collider: Option<&Collider>,
+) -> Vec<Rect> {
+    match hurtboxes {
+        Some(hurtboxes) if !hurtboxes.0.is_empty() => hurtboxes
+            .0
+            .iter()
+            .map(|rect| offset_rect(*rect, position))
+            .collect(),
+        _ => collider
+            .map(|collider| {
+                let aabb = collider.aabb(position, Rotation::default());
+
+                Rect::from_corners(aabb.min, aabb.max)
+            })
+            .into_iter()
+            .collect(),
+    }
+}
+
+fn offset_rect(rect: Rect, offset: Vec2) -> Rect {
+    Rect {
+        min: rect.min + offset,
+        max: rect.max + offset,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hitbox(rect: Rect) -> ActiveHitbox {
+        ActiveHitbox {
+            rect,
+            damage: 5,
+            knockback: KnockbackProfile::from_force(2.),
+            damage_type: DamageType::default(),
+            effects: Vec::new(),
+        }
+    }
+
+    fn animation(name: &str, frame: u32) -> SpriteAnimation {
+        SpriteAnimation {
+            animation_name: name.to_string(),
+            animation_frame: frame,
+            ..default()
+        }
+    }
+
+    #[test]
+    fn hit_entities_are_kept_while_boxes_stay_active() {
+        let target = Entity::from_raw(7);
+        let mut hitboxes = ActiveHitboxes::default();
+        let (square, wide) = (Rect::new(0., 0., 8., 8.), Rect::new(0., 0., 16., 8.));
+
+        hitboxes.set(&animation("attack", 1), vec![hitbox(square)]);
+        hitboxes.hit_entities.insert(target);
+        hitboxes.set(&animation("attack", 1), vec![hitbox(wide)]);
+        hitboxes.set(&animation("attack", 2), vec![hitbox(wide)]);
+
+        assert!(hitboxes.hit_entities.contains(&target));
+    }
+
+    #[test]
+    fn boxes_after_a_frame_without_any_start_a_new_attack() {
+        let target = Entity::from_raw(7);
+        let mut hitboxes = ActiveHitboxes::default();
+        let square = Rect::new(0., 0., 8., 8.);
+
+        hitboxes.set(&animation("attack", 1), vec![hitbox(square)]);
+        hitboxes.hit_entities.insert(target);
+        hitboxes.set(&animation("attack", 2), Vec::new());
+
+        // The frame without boxes only ends the attack, the next boxes reset the hits
+        assert!(hitboxes.hit_entities.contains(&target));
+
+        hitboxes.set(&animation("attack", 3), vec![hitbox(square)]);
+
+        assert!(hitboxes.hit_entities.is_empty());
+    }
+
+    #[test]
+    fn another_animation_starts_a_new_attack() {
+        let target = Entity::from_raw(7);
+        let mut hitboxes = ActiveHitboxes::default();
+        let square = Rect::new(0., 0., 8., 8.);
+
+        hitboxes.set(&animation("attack", 1), vec![hitbox(square)]);
+        hitboxes.hit_entities.insert(target);
+        hitboxes.set(&animation("attack_up", 1), vec![hitbox(square)]);
+
+        assert!(hitboxes.hit_entities.is_empty());
+    }
+
+    #[test]
+    fn restarted_animation_starts_a_new_attack() {
+        let target = Entity::from_raw(7);
+        let mut hitboxes = ActiveHitboxes::default();
+        let square = Rect::new(0., 0., 8., 8.);
+
+        hitboxes.set(&animation("spin", 3), vec![hitbox(square)]);
+        hitboxes.hit_entities.insert(target);
+        hitboxes.set(&animation("spin", 0), vec![hitbox(square)]);
+
+        assert!(hitboxes.hit_entities.is_empty());
+    }
+
+    #[test]
+    fn hurtboxes_are_placed_at_the_target() {
+        let hurtboxes = ActiveHurtboxes(vec![Rect::new(-2., -4., 2., 4.)]);
+        let collider = Collider::rectangle(16., 16.);
+
+        let rects = hurt_rects(Vec2::new(10., 20.), Some(&hurtboxes), Some(&collider));
+
+        assert_eq!(rects, vec![Rect::new(8., 16., 12., 24.)]);
+    }
+
+    #[test]
+    fn targets_without_hurtboxes_fall_back_to_their_collider() {
+        let collider = Collider::rectangle(16., 8.);
+        let expected = vec![Rect::new(2., 16., 18., 24.)];
+
+        assert_eq!(
+            hurt_rects(Vec2::new(10., 20.), None, Some(&collider)),
+            expected
+        );
+        assert_eq!(
+            hurt_rects(
+                Vec2::new(10., 20.),
+                Some(&ActiveHurtboxes::default()),
+                Some(&collider)
+            ),
+            expected
+        );
+        assert!(hurt_rects(Vec2::new(10., 20.), None, None).is_empty());
+    }
+}
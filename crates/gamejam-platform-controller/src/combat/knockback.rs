@@ -0,0 +1,204 @@
+use crate::movement_systems::movement_components::ApplyTimedLinearVelocity;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Acceleration of the knockback over its duration, from the initial impulse down to nothing
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize, Reflect)]
+#[serde(rename_all = "kebab-case")]
+pub enum KnockbackDecay {
+    #[default]
+    Linear,
+    /// Most of the push happens right after the hit
+    Quadratic,
+    /// Full impulse until the knockback ends
+    Constant,
+}
+
+impl KnockbackDecay {
+    /// Share of the impulse applied with `remaining` (1 to 0) of the knockback left
+    fn factor(self, remaining: f32) -> f32 {
+        match self {
+            KnockbackDecay::Linear => remaining,
+            KnockbackDecay::Quadratic => remaining * remaining,
+            KnockbackDecay::Constant => 1.,
+        }
+    }
+}
+
+/// How an attack pushes back whatever it hits
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize, Reflect)]
+pub struct KnockbackProfile {
+    /// Degrees above the horizontal, pointing away from the attacker. Without one the push
+    /// keeps the horizontal share of the attack, lifted by 1.2 before normalizing.
+    #[serde(default)]
+    pub angle_degrees: Option<f32>,
+    /// Acceleration at the start of the knockback
+    pub impulse: f32,
+    pub duration_millis: u32,
+    #[serde(default)]
+    pub decay: KnockbackDecay,
+}
+
+impl KnockbackProfile {
+    /// Pushback of attacks only described by a force
+    pub fn from_force(force: f32) -> Self {
+        Self {
+            angle_degrees: None,
+            impulse: 3000.,
+            duration_millis: ((0.1 + force * 0.03) * 1000.).round() as u32,
+            decay: KnockbackDecay::Linear,
+        }
+    }
+
+    /// Launch direction for an attack travelling along `attack_vector`
+    fn launch_direction(&self, attack_vector: Vec2) -> Vec2 {
+        let Some(angle_degrees) = self.angle_degrees else {
+            return vec2(attack_vector.normalize_or_zero().x, 1.2).normalize();
+        };
+
+        let launch = Vec2::from_angle(angle_degrees.to_radians());
+
+        if attack_vector.x < 0. {
+            vec2(-launch.x, launch.y)
+        } else {
+            launch
+        }
+    }
+
+    /// Acceleration at the start of the knockback, scaled by the receiver's weight class
+    fn initial_acceleration(&self, attack_vector: Vec2, weight_class: WeightClass) -> Vec2 {
+        self.launch_direction(attack_vector) * self.impulse * weight_class.impulse_factor()
+    }
+}
+
+impl Default for KnockbackProfile {
+    fn default() -> Self {
+        Self::from_force(0.)
+    }
+}
+
+/// How much an entity gets pushed around by knockback, entities without one are medium
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum WeightClass {
+    Light,
+    #[default]
+    Medium,
+    Heavy,
+}
+
+impl WeightClass {
+    fn impulse_factor(self) -> f32 {
+        match self {
+            WeightClass::Light => 1.5,
+            WeightClass::Medium => 1.,
+            WeightClass::Heavy => 0.3,
+        }
+    }
+}
+
+/// Knocks the entity back along the profile, scaled by its weight class
+pub fn apply_knockback(
+    commands: &mut EntityCommands,
+    profile: KnockbackProfile,
+    attack_vector: Vec2,
+    weight_class: WeightClass,
+) {
+    if profile.duration_millis == 0 {
+        return;
+    }
+
+    let duration = Duration::from_millis(profile.duration_millis as u64);
+    let duration_secs = duration.as_secs_f32();
+    let acceleration = profile.initial_acceleration(attack_vector, weight_class);
+
+    commands.insert(ApplyTimedLinearVelocity {
+        timer: Timer::new(duration, TimerMode::Once),
+        acceleration_function: Box::new(move |remaining_time: f32| {
+            acceleration * profile.decay.factor(remaining_time / duration_secs)
+        }),
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: Vec2, expected: Vec2) {
+        assert!(
+            actual.abs_diff_eq(expected, 1e-5),
+            "{actual} is not {expected}"
+        );
+    }
+
+    #[test]
+    fn force_sets_the_duration() {
+        assert_eq!(KnockbackProfile::from_force(0.).duration_millis, 100);
+        assert_eq!(KnockbackProfile::from_force(2.).duration_millis, 160);
+        assert_eq!(KnockbackProfile::from_force(7.).duration_millis, 310);
+    }
+
+    #[test]
+    fn force_pushback_keeps_the_horizontal_share_of_the_attack() {
+        let profile = KnockbackProfile::from_force(2.);
+
+        for attack_vector in [
+            vec2(1., 0.),
+            vec2(-3., 0.),
+            vec2(0., -1.),
+            vec2(1., 1.),
+            vec2(-2., 5.),
+        ] {
+            assert_close(
+                profile.launch_direction(attack_vector),
+                vec2(attack_vector.normalize().x, 1.2).normalize(),
+            );
+        }
+
+        assert_close(profile.launch_direction(vec2(0., -1.)), Vec2::Y);
+        assert_close(profile.launch_direction(Vec2::ZERO), Vec2::Y);
+    }
+
+    #[test]
+    fn angled_pushback_points_away_from_the_attacker() {
+        let profile = KnockbackProfile {
+            angle_degrees: Some(45.),
+            ..KnockbackProfile::from_force(2.)
+        };
+        let launch = Vec2::from_angle(45f32.to_radians());
+
+        assert_close(profile.launch_direction(vec2(0.2, -1.)), launch);
+        assert_close(
+            profile.launch_direction(vec2(-0.2, -1.)),
+            vec2(-launch.x, launch.y),
+        );
+    }
+
+    #[test]
+    fn decay_scales_the_impulse_by_the_remaining_share() {
+        assert_eq!(KnockbackDecay::Linear.factor(1.), 1.);
+        assert_eq!(KnockbackDecay::Linear.factor(0.5), 0.5);
+        assert_eq!(KnockbackDecay::Quadratic.factor(1.), 1.);
+        assert_eq!(KnockbackDecay::Quadratic.factor(0.5), 0.25);
+        assert_eq!(KnockbackDecay::Constant.factor(0.5), 1.);
+        assert_eq!(KnockbackDecay::Constant.factor(0.), 1.);
+    }
+
+    #[test]
+    fn weight_class_scales_the_impulse() {
+        let profile = KnockbackProfile::from_force(2.);
+        let medium = profile.initial_acceleration(Vec2::X, WeightClass::Medium);
+
+        assert_close(medium, vec2(1., 1.2).normalize() * 3000.);
+        assert_close(
+            profile.initial_acceleration(Vec2::X, WeightClass::Light),
+            medium * 1.5,
+        );
+        assert_close(
+            profile.initial_acceleration(Vec2::X, WeightClass::Heavy),
+            medium * 0.3,
+        );
+        assert_eq!(WeightClass::default(), WeightClass::Medium);
+    }
+}
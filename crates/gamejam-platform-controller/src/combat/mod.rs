@@ -1,17 +1,23 @@
 pub mod attackable;
 pub mod combat_components;
 pub mod enemy;
+pub mod hitboxes;
+pub mod knockback;
 mod hit_points;
 pub mod projectiles;
 pub mod scheduled_attack_system;
 pub mod stats_system;
+pub mod status_effects;
 
 use crate::combat::attackable::{Attackable, AttackablePlugin};
 use crate::combat::enemy::spawn_enemy_observer;
 use crate::combat::hit_points::{boss_health_system, hit_points_system};
+use crate::combat::hitboxes::hitbox_overlap_system;
 use crate::combat::projectiles::projectile_collision_system;
 use crate::combat::scheduled_attack_system::scheduled_attack_system;
 use crate::combat::stats_system::stats_system;
+use crate::combat::status_effects::status_effect_system;
+use crate::graphics::animation_system::animated_sprite_system;
 use crate::movement_systems::movement_components::FacingDirection;
 use crate::movement_systems::movement_components::MovementData;
 use crate::player_const_rules::{COLLISION_MARGIN, FALL_GRAVITY, X_DAMPENING_FACTOR};
@@ -32,8 +38,10 @@ impl Plugin for EnemyPlugin {
                 FixedUpdate,
                 (
                     scheduled_attack_system,
+                    hitbox_overlap_system.after(animated_sprite_system),
                     hit_points_system,
                     stats_system,
+                    status_effect_system,
                     projectile_collision_system,
                     boss_health_system
                 )
@@ -1,5 +1,6 @@
 use crate::combat::attackable::Attacked;
 use crate::combat::combat_components::Invulnerable;
+use crate::combat::knockback::KnockbackProfile;
 use crate::player_systems::player_components::Player;
 use crate::scripting::scripted_game_entity::EntityScript;
 use avian2d::prelude::*;
@@ -54,10 +55,12 @@ pub fn projectile_collision_system(
                     projectile.collided = true;
 
                     commands.entity(player_entity).insert(Attacked {
+                        attacker: projectile.spawner_entity,
                         damage: 10,
                         origin: transform.translation.xy(),
                         vector: player_transform.translation.xy() - transform.translation.xy(),
-                        force: 7.0,
+                        knockback: KnockbackProfile::from_force(7.0),
+                        ..default()
                     });
                 }
 
@@ -1,14 +1,19 @@
 use crate::combat::attackable::{Attackable, Attacked};
 use crate::combat::combat_components::ScheduledAttack;
+use crate::combat::status_effects::Stunned;
 use avian2d::prelude::Collider;
 use bevy::prelude::{Commands, Entity, Query, Res, Transform, With};
 use bevy::time::Time;
 
+/// Hits whatever the ray of a script scheduled attack crosses once its delay runs out.
+/// Scripts place their own attacks, so these don't use the hitboxes of the sprite file.
+/// Attackers stunned by then don't get to land their attack.
 pub fn scheduled_attack_system(
     mut commands: Commands,
     time: Res<Time>,
     mut attacks: Query<(Entity, &mut ScheduledAttack)>,
     attackables: Query<(Entity, &Transform, &Collider), With<Attackable>>,
+    stunned: Query<(), With<Stunned>>,
 ) {
     let delta = time.delta();
 
@@ -19,6 +24,11 @@ pub fn scheduled_attack_system(
             continue;
         }
 
+        if stunned.contains(attack.attacker) {
+            commands.entity(attack_entity).despawn();
+            continue;
+        }
+
         for (attackable_entity, attackable_transform, collider) in attackables.iter() {
             if attackable_entity == attack.attacker {
                 continue;
@@ -34,10 +44,13 @@ pub fn scheduled_attack_system(
                 distance,
             ) {
                 commands.entity(attackable_entity).insert(Attacked {
+                    attacker: Some(attack.attacker),
                     damage: attack.damage,
                     origin: attack.origin,
                     vector: attack.vector,
-                    force: attack.force
+                    knockback: attack.knockback,
+                    damage_type: attack.damage_type,
+                    effects: attack.effects.clone(),
                 });
             }
         }
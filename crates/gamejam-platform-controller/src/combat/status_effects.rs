@@ -0,0 +1,209 @@
+use crate::combat::combat_components::{DamageType, Health, Resistances, Stamina};
+use crate::movement_systems::movement_components::MovementData;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, Reflect)]
+#[serde(rename_all = "kebab-case")]
+pub enum StatusEffectKind {
+    /// Deals `magnitude` fire damage per second
+    Burn,
+    /// Deals `magnitude` poison damage and drains as much stamina per second
+    Poison,
+    /// Can't move or act
+    Stun,
+    /// Lowers the top speed by the `magnitude` fraction
+    Slow,
+}
+
+/// A status effect carried by an attack or applied by a script
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, Reflect)]
+pub struct StatusEffect {
+    pub kind: StatusEffectKind,
+    pub duration_millis: u32,
+    #[serde(default)]
+    pub magnitude: f32,
+}
+
+#[derive(Debug)]
+struct ActiveStatusEffect {
+    kind: StatusEffectKind,
+    magnitude: f32,
+    remaining: Timer,
+    /// Damage over time not dealt yet, stats only take whole points
+    pending_damage: f32,
+}
+
+/// Status effects an entity is currently under, removed once they all wore off
+#[derive(Component, Default, Debug)]
+pub struct StatusEffects {
+    active: Vec<ActiveStatusEffect>,
+    /// Top speed before slows got applied, restored when they wear off
+    base_max_speed: Option<f32>,
+}
+
+impl StatusEffects {
+    /// Applying an effect that is already active refreshes it, keeping the stronger magnitude
+    pub fn apply(&mut self, effect: StatusEffect) {
+        let duration = Duration::from_millis(effect.duration_millis as u64);
+
+        if let Some(active) = self
+            .active
+            .iter_mut()
+            .find(|active| active.kind == effect.kind)
+        {
+            if active.remaining.remaining() < duration {
+                active.remaining = Timer::new(duration, TimerMode::Once);
+            }
+
+            active.magnitude = active.magnitude.max(effect.magnitude);
+
+            return;
+        }
+
+        self.active.push(ActiveStatusEffect {
+            kind: effect.kind,
+            magnitude: effect.magnitude,
+            remaining: Timer::new(duration, TimerMode::Once),
+            pending_damage: 0.,
+        });
+    }
+
+    pub fn has(&self, kind: StatusEffectKind) -> bool {
+        self.active.iter().any(|active| active.kind == kind)
+    }
+
+    /// Active effects with their remaining duration
+    pub fn effects(&self) -> Vec<StatusEffect> {
+        self.active
+            .iter()
+            .map(|active| StatusEffect {
+                kind: active.kind,
+                duration_millis: active.remaining.remaining().as_millis() as u32,
+                magnitude: active.magnitude,
+            })
+            .collect()
+    }
+
+    fn speed_factor(&self) -> f32 {
+        self.active
+            .iter()
+            .filter(|active| active.kind == StatusEffectKind::Slow)
+            .map(|active| (1. - active.magnitude).clamp(0., 1.))
+            .product()
+    }
+}
+
+/// Attached while a stun effect is active
+#[derive(Component)]
+pub struct Stunned;
+
+/// Applies a status effect to an entity, starting to track its effects if needed
+pub fn apply_status_effect(commands: &mut Commands, target: Entity, effect: StatusEffect) {
+    commands.queue(move |world: &mut World| {
+        let Ok(mut entity) = world.get_entity_mut(target) else {
+            return;
+        };
+
+        match entity.get_mut::<StatusEffects>() {
+            Some(mut status_effects) => status_effects.apply(effect),
+            None => {
+                let mut status_effects = StatusEffects::default();
+                status_effects.apply(effect);
+
+                entity.insert(status_effects);
+            }
+        }
+    });
+}
+
+pub fn status_effect_system(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut affected: Query<(
+        Entity,
+        &mut StatusEffects,
+        Option<&mut Health>,
+        Option<&mut Stamina>,
+        Option<&mut MovementData>,
+        Option<&Resistances>,
+        Has<Stunned>,
+    )>,
+) {
+    let delta = time.delta();
+
+    for (
+        entity,
+        mut status_effects,
+        mut health,
+        mut stamina,
+        movement_data,
+        resistances,
+        stunned,
+    ) in affected.iter_mut()
+    {
+        for active in status_effects.active.iter_mut() {
+            active.remaining.tick(delta);
+
+            let damage_type = match active.kind {
+                StatusEffectKind::Burn => DamageType::Fire,
+                StatusEffectKind::Poison => DamageType::Poison,
+                StatusEffectKind::Stun | StatusEffectKind::Slow => continue,
+            };
+
+            let damage_factor =
+                resistances.map_or(1., |resistances| resistances.damage_factor(damage_type));
+
+            active.pending_damage += active.magnitude * damage_factor * delta.as_secs_f32();
+
+            if active.pending_damage < 1. {
+                continue;
+            }
+
+            let damage = active.pending_damage.floor() as u32;
+            active.pending_damage -= damage as f32;
+
+            if let Some(health) = health.as_mut() {
+                health.0.consume(damage);
+            }
+
+            if active.kind == StatusEffectKind::Poison {
+                if let Some(stamina) = stamina.as_mut() {
+                    stamina.0.consume(damage);
+                }
+            }
+        }
+
+        status_effects
+            .active
+            .retain(|active| !active.remaining.finished());
+
+        // Top speed is only taken over while slowed, and handed back once slows wore off
+        if let Some(mut movement_data) = movement_data {
+            if status_effects.has(StatusEffectKind::Slow) {
+                let base_max_speed = *status_effects
+                    .base_max_speed
+                    .get_or_insert(movement_data.max_speed);
+
+                movement_data.max_speed = base_max_speed * status_effects.speed_factor();
+            } else if let Some(base_max_speed) = status_effects.base_max_speed.take() {
+                movement_data.max_speed = base_max_speed;
+            }
+        }
+
+        match (status_effects.has(StatusEffectKind::Stun), stunned) {
+            (true, false) => {
+                commands.entity(entity).insert(Stunned);
+            }
+            (false, true) => {
+                commands.entity(entity).remove::<Stunned>();
+            }
+            _ => {}
+        }
+
+        if status_effects.active.is_empty() {
+            commands.entity(entity).remove::<StatusEffects>();
+        }
+    }
+}
@@ -1,3 +1,6 @@
+use crate::combat::combat_components::{HitInvulnerability, Resistances};
+use crate::combat::knockback::WeightClass;
+use crate::scripting::script_faults::ScriptFaultPolicy;
 use bevy::asset::{Asset, Handle};
 use bevy::prelude::{Resource, TypePath};
 use serde::{Deserialize, Serialize};
@@ -17,7 +20,19 @@ pub struct GameEntityDefinition {
     /// Path to the script file for this entity
     pub script_path: Option<String>,
     pub script_params: Option<Vec<String>>,
-    pub z: Option<f32>
+    pub z: Option<f32>,
+    /// Overrides the default per-call fuel budget of the script
+    pub fuel_per_call: Option<u64>,
+    /// Overrides the default fault policy of the script
+    pub fault_policy: Option<ScriptFaultPolicy>,
+    /// Looping animation played on spawn, lets prototypes without a script show up
+    pub animation: Option<AnimationDescription>,
+    /// Damage type resistances, e.g. `resistances = { fire = 1.0, frost = -0.5 }`
+    pub resistances: Option<Resistances>,
+    /// Invulnerability after taking a hit, e.g. `{ duration_millis = 300, attacker_cooldown_millis = 500 }`
+    pub hit_invulnerability: Option<HitInvulnerability>,
+    /// How far knockback pushes the entity, `light`, `medium` or `heavy`
+    pub weight_class: Option<WeightClass>,
 }
 
 #[derive(Clone, Serialize, Deserialize)]
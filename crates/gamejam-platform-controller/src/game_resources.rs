@@ -3,8 +3,12 @@ use crate::game_entities::file_formats::game_entity_definitions::{
 };
 use crate::graphics::sprite_collection::{AnimatedSpriteFile, AnimatedSpriteFileHandle};
 use crate::GameStates;
-use bevy::asset::{AssetServer, Handle, LoadedFolder};
+use bevy::asset::{AssetServer, Assets, Handle, LoadedFolder};
+use bevy::log::error;
 use bevy::prelude::{Commands, NextState, Res, ResMut, Resource};
+use bevy_wasmer_scripting::component_cache::ComponentCache;
+use bevy_wasmer_scripting::scripted_entity::WasmEngine;
+use bevy_wasmer_scripting::wasm_script_asset::{CompiledComponents, WasmScriptModuleBytes};
 
 #[derive(Resource)]
 pub struct ScriptLoaderHandle(pub Handle<LoadedFolder>);
@@ -22,12 +26,31 @@ pub fn load_resources(mut commands: Commands, assets: ResMut<AssetServer>) {
 
 pub fn load_scripts_system(
     assets: ResMut<AssetServer>,
+    engine: Res<WasmEngine>,
+    component_cache: Option<Res<ComponentCache>>,
     loading_handle: Res<ScriptLoaderHandle>,
+    wasm_scripts: Res<Assets<WasmScriptModuleBytes>>,
+    mut compiled_components: ResMut<CompiledComponents>,
     mut next_state: ResMut<NextState<GameStates>>,
 ) {
     let state = assets.get_load_state(&loading_handle.0).unwrap();
 
     if state.is_loaded() {
+        // Compile every script up front, so entering a level never waits on cranelift
+        for (id, script) in wasm_scripts.iter() {
+            if let Err(e) = compiled_components.get_or_compile(
+                id,
+                script,
+                &engine.0,
+                component_cache.as_deref(),
+            ) {
+                error!(
+                    "failed to precompile script {:?}: {e:?}",
+                    assets.get_path(id)
+                );
+            }
+        }
+
         next_state.set(GameStates::LoadingSprites);
     }
 }
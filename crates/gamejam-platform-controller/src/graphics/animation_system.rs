@@ -1,3 +1,6 @@
+use crate::combat::hitboxes::{update_frame_boxes, ActiveHitboxes, ActiveHurtboxes};
+use crate::graphics::sprite_collection::SpriteCollection;
+use crate::movement_systems::movement_components::FacingDirection;
 use crate::scripting::scripted_game_entity::EntityScript;
 #[allow(unused_imports)]
 use avian2d::prelude::RigidBody;
@@ -14,6 +17,8 @@ pub struct SpriteAnimation {
     pub repeat: bool,
     pub despawn_finished: bool,
     pub animation_name: String,
+    /// Name of the sprite in the sprite collection, used to look up its frame boxes
+    pub sprite_name: String,
     pub sprite_size: UVec2,
 }
 
@@ -33,7 +38,14 @@ fn path() {
             InteractableHintComponent::type_path(),
             RigidBody::type_path()
         ],
-        vec![""]
+        vec![
+            "bevy_sprite::sprite::Sprite",
+            "gamejam_platform_controller::graphics::animation_system::SpriteAnimation",
+            "gamejam_platform_controller::combat::attackable::Attackable",
+            "gamejam_bevy_components::Interactable",
+            "gamejam_platform_controller::ui::interactable_hint::InteractableHintComponent",
+            "avian2d::dynamics::rigid_body::RigidBody",
+        ]
     );
 }
 
@@ -65,14 +77,20 @@ impl SpriteAnimation {
 pub fn animated_sprite_system(
     mut commands: Commands,
     time: Res<Time>,
+    sprites: Res<SpriteCollection>,
     mut sprite: Query<(
         Entity,
         &mut Sprite,
         &mut SpriteAnimation,
         Option<&mut EntityScript>,
+        Option<&FacingDirection>,
+        Option<&mut ActiveHitboxes>,
+        Option<&mut ActiveHurtboxes>,
     )>,
 ) {
-    for (entity, mut sprite, mut animation, script) in sprite.iter_mut() {
+    for (entity, mut sprite, mut animation, script, facing, hitboxes, hurtboxes) in
+        sprite.iter_mut()
+    {
         animation.timer.tick(time.delta());
 
         if animation.timer.finished() {
@@ -88,7 +106,7 @@ pub fn animated_sprite_system(
                 } else {
                     if animation.despawn_finished {
                         commands.entity(entity).despawn();
-                        return;
+                        continue;
                     } else {
                         if let Some(mut script) = script {
                             script.animation_finished(&animation.animation_name);
@@ -100,13 +118,32 @@ pub fn animated_sprite_system(
             }
         }
 
-        let Some(sprite_atlas) = sprite.texture_atlas.as_mut() else {
-            return;
-        };
-
         let frame_index = animation
             .animation_frame
             .min(animation.animation_frame_count - 1);
+
+        if let Some(animation_info) =
+            sprites.animation_info(&animation.sprite_name, &animation.animation_name)
+        {
+            if animation_info.has_frame_boxes() || hitboxes.is_some() || hurtboxes.is_some() {
+                let flip_x = facing.map_or(sprite.flip_x, FacingDirection::to_bool);
+
+                update_frame_boxes(
+                    &mut commands.entity(entity),
+                    &animation,
+                    animation_info,
+                    frame_index,
+                    flip_x,
+                    hitboxes,
+                    hurtboxes,
+                );
+            }
+        }
+
+        let Some(sprite_atlas) = sprite.texture_atlas.as_mut() else {
+            continue;
+        };
+
         sprite_atlas.index = (animation.animation_start_index + frame_index) as usize;
     }
 }
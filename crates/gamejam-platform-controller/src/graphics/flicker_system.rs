@@ -0,0 +1,29 @@
+use bevy::prelude::*;
+
+/// Seconds the sprite stays shown or hidden while flickering
+const FLICKER_INTERVAL_SECONDS: f32 = 0.06;
+
+/// Blinks the sprite on and off for as long as it is attached
+#[derive(Component)]
+pub struct Flickering;
+
+pub fn flicker_system(time: Res<Time>, mut query: Query<&mut Visibility, With<Flickering>>) {
+    let shown = (time.elapsed_secs() / FLICKER_INTERVAL_SECONDS) as u32 % 2 == 0;
+
+    for mut visibility in query.iter_mut() {
+        *visibility = if shown {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+    }
+}
+
+pub fn flickering_removed_observer(
+    trigger: Trigger<OnRemove, Flickering>,
+    mut query: Query<&mut Visibility>,
+) {
+    if let Ok(mut visibility) = query.get_mut(trigger.entity()) {
+        *visibility = Visibility::Inherited;
+    }
+}
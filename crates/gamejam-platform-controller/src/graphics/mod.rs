@@ -1,3 +1,4 @@
 pub mod animation_system;
+pub mod flicker_system;
 pub mod materials;
 pub mod sprite_collection;
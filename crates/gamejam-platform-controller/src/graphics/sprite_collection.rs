@@ -1,3 +1,6 @@
+use crate::combat::combat_components::DamageType;
+use crate::combat::knockback::KnockbackProfile;
+use crate::combat::status_effects::StatusEffect;
 use crate::graphics::animation_system::SpriteAnimation;
 use crate::GameStates;
 use bevy::prelude::*;
@@ -20,6 +23,17 @@ pub struct SpriteCollection {
 }
 
 impl SpriteCollection {
+    pub fn animation_info(
+        &self,
+        sprite_name: &str,
+        animation_name: &str,
+    ) -> Option<&AnimationInfo> {
+        self.sprites
+            .get(sprite_name)?
+            .animations
+            .get(animation_name)
+    }
+
     pub fn create_sprite_animation_bundle(
         &self,
         sprite_name: &str,
@@ -55,6 +69,7 @@ impl SpriteCollection {
             repeat,
             despawn_finished,
             animation_name: animation_name.to_string(),
+            sprite_name: sprite_name.to_string(),
             sprite_size: sprite_info.sprite_size,
         };
 
@@ -98,6 +113,7 @@ impl SpriteCollection {
             repeat,
             despawn_finished,
             animation_name: animation_name.to_string(),
+            sprite_name: sprite_name.to_string(),
             sprite_size: sprite_info.sprite_size,
         };
 
@@ -155,11 +171,72 @@ pub fn spawn_sprite_collection_system(
     }
 }
 
-#[derive(Deserialize, Copy, Clone)]
+#[derive(Deserialize, Clone)]
 pub struct AnimationInfo {
     pub row: u32,
     pub frame_start_index: u32,
     pub frame_count: u32,
+    /// Areas dealing damage while their frames play
+    #[serde(default)]
+    pub hitboxes: Vec<HitboxInfo>,
+    /// Areas that can be hit while their frames play, frames without any fall back to the collider
+    #[serde(default)]
+    pub hurtboxes: Vec<HurtboxInfo>,
+}
+
+impl AnimationInfo {
+    pub fn has_frame_boxes(&self) -> bool {
+        !self.hitboxes.is_empty() || !self.hurtboxes.is_empty()
+    }
+}
+
+/// Boxes are placed relative to the sprite's centre, with y pointing up, for a sprite facing east.
+/// They are mirrored for flipped sprites.
+#[derive(Deserialize, Clone, Debug)]
+pub struct HitboxInfo {
+    /// Frames of the animation the box is active on, counting from 0
+    pub frames: Vec<u32>,
+    pub center: Vec2,
+    pub size: Vec2,
+    pub damage: u32,
+    #[serde(default)]
+    pub force: f32,
+    /// Replaces the pushback scaled by `force`
+    #[serde(default)]
+    pub knockback: Option<KnockbackProfile>,
+    #[serde(default)]
+    pub damage_type: DamageType,
+    #[serde(default)]
+    pub effects: Vec<StatusEffect>,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct HurtboxInfo {
+    pub frames: Vec<u32>,
+    pub center: Vec2,
+    pub size: Vec2,
+}
+
+impl HitboxInfo {
+    pub fn rect(&self, flip_x: bool) -> Rect {
+        frame_box_rect(self.center, self.size, flip_x)
+    }
+}
+
+impl HurtboxInfo {
+    pub fn rect(&self, flip_x: bool) -> Rect {
+        frame_box_rect(self.center, self.size, flip_x)
+    }
+}
+
+fn frame_box_rect(center: Vec2, size: Vec2, flip_x: bool) -> Rect {
+    let center = if flip_x {
+        Vec2::new(-center.x, center.y)
+    } else {
+        center
+    };
+
+    Rect::from_center_size(center, size)
 }
 
 #[derive(Deserialize, Asset, TypePath)]
@@ -177,3 +254,22 @@ pub struct AnimatedSpriteFile {
 
 #[derive(Resource)]
 pub struct AnimatedSpriteFileHandle(pub Handle<AnimatedSpriteFile>);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frame_boxes_keep_their_place_facing_east() {
+        let rect = frame_box_rect(Vec2::new(16., 4.), Vec2::new(32., 20.), false);
+
+        assert_eq!(rect, Rect::new(0., -6., 32., 14.));
+    }
+
+    #[test]
+    fn frame_boxes_are_mirrored_facing_west() {
+        let rect = frame_box_rect(Vec2::new(16., 4.), Vec2::new(32., 20.), true);
+
+        assert_eq!(rect, Rect::new(-32., -6., 0., 14.));
+    }
+}
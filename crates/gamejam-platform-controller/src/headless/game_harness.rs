@@ -0,0 +1,235 @@
+use crate::game_entities::file_formats::game_entity_definitions::GameEntityDefinitionFile;
+use crate::headless::HeadlessPlugin;
+use crate::input_systems::input_map::InputMap;
+use crate::input_systems::input_replay::{
+    InputPlayback, InputRecorder, InputReplayMode, InputTick,
+};
+use crate::input_systems::PlayerInputAction;
+use crate::ldtk_entities::player_spawn::RequestedPlayerSpawn;
+use crate::player_systems::player_components::Player;
+use crate::scripting::scripted_game_entity::GameData;
+use crate::GameStates;
+use anyhow::bail;
+use bevy::prelude::*;
+use std::path::{Path, PathBuf};
+
+/// Assets of the game crate, which the harness plays with by default
+const GAME_ASSET_ROOT: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../game/assets");
+
+/// Level the game starts in
+pub const DEFAULT_LEVEL: &str = "maps/grayboxes/nexus_ldtk/nexus.ldtk";
+
+/// Drives a headless game frame by frame, for tests asserting on the world
+pub struct GameHarness {
+    pub app: App,
+}
+
+impl GameHarness {
+    /// Builds a headless game using the assets of the game crate
+    pub fn new(level_path: &str) -> Self {
+        Self::with_asset_root(GAME_ASSET_ROOT, level_path)
+    }
+
+    /// Builds a headless game that plays back a recorded input replay instead of taking input
+    pub fn from_replay(level_path: &str, replay_path: impl Into<PathBuf>) -> Self {
+        Self::build(
+            GAME_ASSET_ROOT,
+            level_path,
+            InputReplayMode::Replay(replay_path.into()),
+        )
+    }
+
+    /// Game crate assets a run needs that aren't checked in, the level and the compiled scripts.
+    /// Scripts are built by `crates/scripts/build_scripts.sh`.
+    pub fn missing_assets(level_path: &str) -> Vec<PathBuf> {
+        let asset_root = Path::new(GAME_ASSET_ROOT);
+        let mut required = vec![asset_root.join(level_path)];
+
+        let entity_db = std::fs::read_to_string(asset_root.join("entities/entities.toml"))
+            .ok()
+            .and_then(|file| toml::from_str::<GameEntityDefinitionFile>(&file).ok());
+
+        if let Some(entity_db) = entity_db {
+            required.extend(
+                entity_db
+                    .entities
+                    .values()
+                    .filter_map(|entity| entity.script_path.as_ref())
+                    .map(|script_path| asset_root.join(script_path)),
+            );
+        }
+
+        required.sort();
+        required.dedup();
+        required.retain(|path| !path.exists());
+
+        required
+    }
+
+    /// Builds a headless game that records the input it gets to a replay file
+    pub fn recording(level_path: &str, replay_path: impl Into<PathBuf>) -> Self {
+        Self::build(
+            GAME_ASSET_ROOT,
+            level_path,
+            InputReplayMode::Record(replay_path.into()),
+        )
+    }
+
+    pub fn with_asset_root(asset_root: &str, level_path: &str) -> Self {
+        Self::build(asset_root, level_path, InputReplayMode::Off)
+    }
+
+    fn build(asset_root: &str, level_path: &str, input_replay: InputReplayMode) -> Self {
+        let mut app = App::new();
+
+        app.insert_resource(input_replay)
+            .insert_resource(InputMap::default())
+            .add_plugins(HeadlessPlugin {
+                asset_root: asset_root.to_string(),
+                level_path: level_path.to_string(),
+            });
+
+        app.finish();
+        app.cleanup();
+
+        Self { app }
+    }
+
+    /// Runs frames until the player stands at its spawn point in a running level
+    pub fn run_until_playing(&mut self, max_frames: u32) -> anyhow::Result<()> {
+        for _ in 0..max_frames {
+            self.step();
+
+            if self.is_playing() {
+                return Ok(());
+            }
+        }
+
+        bail!(
+            "game did not start within {max_frames} frames, stuck in {:?}",
+            self.state()
+        )
+    }
+
+    fn is_playing(&mut self) -> bool {
+        if self.state() != GameStates::GameLoop {
+            return false;
+        }
+
+        let world = self.app.world_mut();
+
+        world
+            .query_filtered::<(), (With<Player>, Without<RequestedPlayerSpawn>)>()
+            .iter(world)
+            .next()
+            .is_some()
+    }
+
+    /// Advances the game by one fixed timestep
+    pub fn step(&mut self) {
+        self.app.update();
+    }
+
+    /// Advances one frame with the given input, as if it came from the keyboard or a gamepad
+    pub fn step_with(&mut self, actions: &[PlayerInputAction]) {
+        for action in actions {
+            self.app.world_mut().send_event(action.clone());
+        }
+
+        self.step();
+    }
+
+    /// Advances a number of frames, sending the same input on each of them
+    pub fn run_frames(&mut self, frames: u32, actions: &[PlayerInputAction]) {
+        for _ in 0..frames {
+            self.step_with(actions);
+        }
+    }
+
+    /// Runs frames until every input of the replay has been played back
+    pub fn run_replay(&mut self, max_frames: u32) -> anyhow::Result<()> {
+        for _ in 0..max_frames {
+            let finished = self
+                .app
+                .world()
+                .get_resource::<InputPlayback>()
+                .is_some_and(InputPlayback::is_finished);
+
+            if finished {
+                return Ok(());
+            }
+
+            self.step();
+        }
+
+        bail!("replay did not finish within {max_frames} frames")
+    }
+
+    /// Writes the input recorded so far, recordings are otherwise only written now and then
+    pub fn flush_recording(&self) {
+        if let Some(recorder) = self.app.world().get_resource::<InputRecorder>() {
+            recorder.flush();
+        }
+    }
+
+    /// Game loop ticks so far, what recorded input is timed by
+    pub fn input_tick(&self) -> u64 {
+        self.app.world().resource::<InputTick>().0
+    }
+
+    pub fn state(&self) -> GameStates {
+        self.app.world().resource::<State<GameStates>>().get().clone()
+    }
+
+    pub fn player(&mut self) -> Entity {
+        let world = self.app.world_mut();
+
+        world
+            .query_filtered::<Entity, With<Player>>()
+            .single(world)
+    }
+
+    pub fn player_position(&mut self) -> Vec2 {
+        let player = self.player();
+
+        self.app
+            .world()
+            .get::<Transform>(player)
+            .expect("player has no transform")
+            .translation
+            .truncate()
+    }
+
+    pub fn player_has<T: Component>(&mut self) -> bool {
+        let player = self.player();
+
+        self.app.world().get::<T>(player).is_some()
+    }
+
+    /// Reads a global int from the script game state
+    pub fn game_state_int(&self, key: &str) -> Option<i32> {
+        let game_data = self.app.world().resource::<GameData>();
+        let game_state = game_data.game_state.lock().unwrap();
+
+        game_state.ints.get(key).copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_level_is_reported() {
+        let missing = GameHarness::missing_assets("maps/not_a_level.ldtk");
+
+        assert!(missing.contains(&Path::new(GAME_ASSET_ROOT).join("maps/not_a_level.ldtk")));
+    }
+
+    #[test]
+    fn assets_that_exist_are_not_reported() {
+        let missing = GameHarness::missing_assets("entities/entities.toml");
+
+        assert!(!missing.contains(&Path::new(GAME_ASSET_ROOT).join("entities/entities.toml")));
+    }
+}
@@ -0,0 +1,75 @@
+use crate::levels::world_plugin::WorldPlugin;
+use crate::{GameStates, PlatformerGameplayPlugin};
+use bevy::audio::AudioSource;
+use bevy::render::render_resource::Shader;
+use bevy::state::app::StatesPlugin;
+use bevy::time::TimeUpdateStrategy;
+use bevy::prelude::*;
+use bevy_ecs_ldtk::LdtkWorldBundle;
+use bevy_trauma_shake::Shake;
+
+pub mod game_harness;
+
+/// Runs the gameplay on `MinimalPlugins`, without a window, GPU or audio device.
+/// Every `App::update` advances the game by exactly one fixed timestep, which keeps runs reproducible.
+pub struct HeadlessPlugin {
+    /// Asset folder, usually the one of the game crate
+    pub asset_root: String,
+    /// LDtk project loaded in place of the main menu
+    pub level_path: String,
+}
+
+#[derive(Resource)]
+struct HeadlessLevel(String);
+
+impl Plugin for HeadlessPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins((
+            MinimalPlugins,
+            AssetPlugin {
+                file_path: self.asset_root.clone(),
+                ..default()
+            },
+            StatesPlugin,
+            TransformPlugin,
+            HierarchyPlugin,
+            bevy::input::InputPlugin,
+            ImagePlugin::default_nearest(),
+        ))
+        // Stand-ins for the asset types the rendering, audio and ui plugins would register,
+        // gameplay code still loads and stores handles to them
+        .init_asset::<TextureAtlasLayout>()
+        .init_asset::<Mesh>()
+        .init_asset::<ColorMaterial>()
+        .init_asset::<Shader>()
+        .init_asset::<AudioSource>()
+        .init_asset::<Font>()
+        .insert_resource(TimeUpdateStrategy::ManualDuration(
+            Time::<Fixed>::default().timestep(),
+        ))
+        .insert_resource(HeadlessLevel(self.level_path.clone()))
+        .add_plugins((WorldPlugin, PlatformerGameplayPlugin))
+        .add_systems(Startup, spawn_headless_camera_system)
+        .add_systems(OnEnter(GameStates::MainMenu), start_headless_level_system);
+    }
+}
+
+/// Level transitions and player spawning follow the camera, so one has to exist
+fn spawn_headless_camera_system(mut commands: Commands) {
+    commands.spawn((Camera2d, Shake::default()));
+}
+
+/// Skips the main menu, loading straight into the level
+fn start_headless_level_system(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    level: Res<HeadlessLevel>,
+    mut next_state: ResMut<NextState<GameStates>>,
+) {
+    commands.spawn(LdtkWorldBundle {
+        ldtk_handle: asset_server.load(&level.0).into(),
+        ..default()
+    });
+
+    next_state.set(GameStates::LoadLevel);
+}
@@ -1,3 +1,4 @@
+use crate::input_systems::input_map::{InputMap, InputMapAction};
 use crate::input_systems::PlayerInputAction;
 use crate::AttackDirection;
 use bevy::prelude::*;
@@ -12,6 +13,7 @@ pub fn gamepad_input_system(
     mut event_sender: EventWriter<PlayerInputAction>,
     mut stats: ResMut<GamepadInputStates>,
     gamepad_query: Query<&Gamepad>,
+    input_map: Res<InputMap>,
 ) {
     let mut direction = Vec2::ZERO;
     
@@ -19,6 +21,9 @@ pub fn gamepad_input_system(
         return;
     };
 
+    let pressed = |action| input_map.button_pressed(action, gamepad);
+    let just_pressed = |action| input_map.button_just_pressed(action, gamepad);
+
     let mut left_stick = gamepad.left_stick();
     let left_stick_down = left_stick.y < -0.4;
     let left_stick_up = left_stick.y > 0.5;
@@ -31,31 +36,41 @@ pub fn gamepad_input_system(
         stats.left_stick_tapped_up = true;
     }
 
-    if gamepad.pressed(GamepadButton::RightTrigger2) {
+    if pressed(InputMapAction::RollRight)
+        || (pressed(InputMapAction::Roll) && pressed(InputMapAction::MoveRight))
+    {
         event_sender.send(PlayerInputAction::Roll(FacingDirection::East));
         return;
     }
 
-    if gamepad.pressed(GamepadButton::LeftTrigger2) {
+    if pressed(InputMapAction::RollLeft)
+        || (pressed(InputMapAction::Roll) && pressed(InputMapAction::MoveLeft))
+    {
         event_sender.send(PlayerInputAction::Roll(FacingDirection::West));
         return;
     }
 
-    if (left_stick_down || gamepad.pressed(GamepadButton::DPadDown))
-        && gamepad.just_pressed(GamepadButton::West)
-    {
+    if (left_stick_down || pressed(InputMapAction::Down)) && just_pressed(InputMapAction::Attack) {
         event_sender.send(PlayerInputAction::Attack(AttackDirection::Down));
-    } else if gamepad.just_pressed(GamepadButton::West) {
+    } else if just_pressed(InputMapAction::Attack) {
         event_sender.send(PlayerInputAction::Attack(AttackDirection::Sideways));
     }
 
-    if gamepad.just_pressed(GamepadButton::DPadUp) || left_stick_up_tapped {
+    if just_pressed(InputMapAction::Interact) || left_stick_up_tapped {
         event_sender.send(PlayerInputAction::Interact);
     }
 
-    if gamepad.pressed(GamepadButton::DPadRight) {
+    if just_pressed(InputMapAction::Dash) {
+        event_sender.send(PlayerInputAction::Dash);
+    }
+
+    if just_pressed(InputMapAction::Parry) {
+        event_sender.send(PlayerInputAction::Parry);
+    }
+
+    if pressed(InputMapAction::MoveRight) {
         direction.x = 2.;
-    } else if gamepad.pressed(GamepadButton::DPadLeft) {
+    } else if pressed(InputMapAction::MoveLeft) {
         direction.x = -2.;
     }
 
@@ -73,15 +88,15 @@ pub fn gamepad_input_system(
         event_sender.send(PlayerInputAction::Horizontal(left_stick));
     }
 
-    if gamepad.pressed(GamepadButton::South) {
+    if pressed(InputMapAction::Jump) {
         event_sender.send(PlayerInputAction::Jump);
     }
 
-    if gamepad.just_pressed(GamepadButton::South) {
+    if just_pressed(InputMapAction::Jump) {
         event_sender.send(PlayerInputAction::JumpStart);
     }
 
-    if gamepad.just_released(GamepadButton::South) {
+    if input_map.button_just_released(InputMapAction::Jump, gamepad) {
         event_sender.send(PlayerInputAction::JumpAbort);
     }
 }
@@ -0,0 +1,372 @@
+use crate::save_game::save_file::game_data_directory;
+use bevy::input::ButtonInput;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+/// Something the player can bind keys and buttons to
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "kebab-case")]
+pub enum InputMapAction {
+    MoveLeft,
+    MoveRight,
+    /// Held to attack downwards
+    Down,
+    Jump,
+    Attack,
+    /// Held along with a direction to roll that way
+    Roll,
+    RollLeft,
+    RollRight,
+    Dash,
+    Parry,
+    Interact,
+}
+
+impl InputMapAction {
+    pub const ALL: [Self; 11] = [
+        Self::MoveLeft,
+        Self::MoveRight,
+        Self::Down,
+        Self::Jump,
+        Self::Attack,
+        Self::Roll,
+        Self::RollLeft,
+        Self::RollRight,
+        Self::Dash,
+        Self::Parry,
+        Self::Interact,
+    ];
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            Self::MoveLeft => "Move left",
+            Self::MoveRight => "Move right",
+            Self::Down => "Down",
+            Self::Jump => "Jump",
+            Self::Attack => "Attack",
+            Self::Roll => "Roll",
+            Self::RollLeft => "Roll left",
+            Self::RollRight => "Roll right",
+            Self::Dash => "Dash",
+            Self::Parry => "Parry",
+            Self::Interact => "Interact",
+        }
+    }
+}
+
+/// Keys and gamepad buttons bound to each action, persisted in `input_map.toml`
+#[derive(Resource, Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct InputMap {
+    pub keyboard: BTreeMap<InputMapAction, Vec<KeyCode>>,
+    pub gamepad: BTreeMap<InputMapAction, Vec<GamepadButton>>,
+}
+
+impl Default for InputMap {
+    fn default() -> Self {
+        Self {
+            keyboard: BTreeMap::from([
+                (InputMapAction::MoveLeft, vec![KeyCode::ArrowLeft, KeyCode::KeyA]),
+                (InputMapAction::MoveRight, vec![KeyCode::ArrowRight, KeyCode::KeyD]),
+                (InputMapAction::Down, vec![KeyCode::ArrowDown]),
+                (InputMapAction::Jump, vec![KeyCode::Space]),
+                (InputMapAction::Attack, vec![KeyCode::KeyF]),
+                (InputMapAction::Roll, vec![KeyCode::ControlLeft]),
+                (InputMapAction::Dash, vec![KeyCode::ShiftLeft]),
+                (InputMapAction::Parry, vec![KeyCode::KeyE]),
+                (InputMapAction::Interact, vec![KeyCode::ArrowUp]),
+            ]),
+            gamepad: BTreeMap::from([
+                (InputMapAction::MoveLeft, vec![GamepadButton::DPadLeft]),
+                (InputMapAction::MoveRight, vec![GamepadButton::DPadRight]),
+                (InputMapAction::Down, vec![GamepadButton::DPadDown]),
+                (InputMapAction::Jump, vec![GamepadButton::South]),
+                (InputMapAction::Attack, vec![GamepadButton::West]),
+                (InputMapAction::RollLeft, vec![GamepadButton::LeftTrigger2]),
+                (InputMapAction::RollRight, vec![GamepadButton::RightTrigger2]),
+                (InputMapAction::Dash, vec![GamepadButton::RightTrigger]),
+                (InputMapAction::Parry, vec![GamepadButton::LeftTrigger]),
+                (InputMapAction::Interact, vec![GamepadButton::DPadUp]),
+            ]),
+        }
+    }
+}
+
+impl InputMap {
+    pub fn path() -> PathBuf {
+        game_data_directory().join("input_map.toml")
+    }
+
+    /// Reads the player's bindings, falling back to the defaults
+    pub fn load() -> Self {
+        let path = Self::path();
+
+        if !path.exists() {
+            return Self::default();
+        }
+
+        let input_map = fs::read_to_string(&path)
+            .map_err(anyhow::Error::from)
+            .and_then(|input_map| Ok(toml::from_str(&input_map)?));
+
+        input_map
+            .map(Self::with_default_bindings_for_new_actions)
+            .unwrap_or_else(|e| {
+                error!("failed to read input map {}: {e:?}", path.display());
+                Self::default()
+            })
+    }
+
+    /// Actions added after the map was saved would otherwise stay unbound
+    fn with_default_bindings_for_new_actions(mut self) -> Self {
+        let defaults = Self::default();
+
+        for action in InputMapAction::ALL {
+            if self.keyboard.contains_key(&action) || self.gamepad.contains_key(&action) {
+                continue;
+            }
+
+            if let Some(keys) = defaults.keyboard.get(&action) {
+                self.keyboard.insert(action, keys.clone());
+            }
+
+            if let Some(buttons) = defaults.gamepad.get(&action) {
+                self.gamepad.insert(action, buttons.clone());
+            }
+        }
+
+        self
+    }
+
+    pub fn save(&self) -> anyhow::Result<()> {
+        fs::create_dir_all(game_data_directory())?;
+        fs::write(Self::path(), toml::to_string_pretty(self)?)?;
+
+        Ok(())
+    }
+
+    /// Makes the key the main binding of the action, keeping its other bindings.
+    /// An action the key was bound to gets the replaced binding in exchange.
+    pub fn bind_key(&mut self, action: InputMapAction, key: KeyCode) {
+        rebind(&mut self.keyboard, action, key);
+    }
+
+    /// Makes the button the main binding of the action, like [`InputMap::bind_key`]
+    pub fn bind_button(&mut self, action: InputMapAction, button: GamepadButton) {
+        rebind(&mut self.gamepad, action, button);
+    }
+
+    fn keys(&self, action: InputMapAction) -> &[KeyCode] {
+        self.keyboard.get(&action).map(Vec::as_slice).unwrap_or_default()
+    }
+
+    fn buttons(&self, action: InputMapAction) -> &[GamepadButton] {
+        self.gamepad.get(&action).map(Vec::as_slice).unwrap_or_default()
+    }
+
+    pub fn key_pressed(&self, action: InputMapAction, keys: &ButtonInput<KeyCode>) -> bool {
+        keys.any_pressed(self.keys(action).iter().copied())
+    }
+
+    pub fn key_just_pressed(&self, action: InputMapAction, keys: &ButtonInput<KeyCode>) -> bool {
+        keys.any_just_pressed(self.keys(action).iter().copied())
+    }
+
+    pub fn key_just_released(&self, action: InputMapAction, keys: &ButtonInput<KeyCode>) -> bool {
+        keys.any_just_released(self.keys(action).iter().copied())
+    }
+
+    pub fn button_pressed(&self, action: InputMapAction, gamepad: &Gamepad) -> bool {
+        self.buttons(action)
+            .iter()
+            .any(|button| gamepad.pressed(*button))
+    }
+
+    pub fn button_just_pressed(&self, action: InputMapAction, gamepad: &Gamepad) -> bool {
+        self.buttons(action)
+            .iter()
+            .any(|button| gamepad.just_pressed(*button))
+    }
+
+    pub fn button_just_released(&self, action: InputMapAction, gamepad: &Gamepad) -> bool {
+        self.buttons(action)
+            .iter()
+            .any(|button| gamepad.just_released(*button))
+    }
+
+    /// Human readable bindings of an action, as shown on the controls page
+    pub fn describe(&self, action: InputMapAction) -> String {
+        let bindings: Vec<String> = self
+            .keys(action)
+            .iter()
+            .map(|key| format!("{key:?}"))
+            .chain(self.buttons(action).iter().map(|button| format!("{button:?}")))
+            .collect();
+
+        if bindings.is_empty() {
+            "unbound".to_string()
+        } else {
+            bindings.join(" / ")
+        }
+    }
+}
+
+fn rebind<T: Copy + PartialEq>(
+    bindings: &mut BTreeMap<InputMapAction, Vec<T>>,
+    action: InputMapAction,
+    input: T,
+) {
+    let action_bindings = bindings.entry(action).or_default();
+
+    if action_bindings.contains(&input) {
+        return;
+    }
+
+    let replaced = action_bindings.first().copied();
+
+    match action_bindings.first_mut() {
+        Some(main_binding) => *main_binding = input,
+        None => action_bindings.push(input),
+    }
+
+    for (other_action, other_bindings) in bindings.iter_mut() {
+        if *other_action == action {
+            continue;
+        }
+
+        let Some(index) = other_bindings.iter().position(|other| *other == input) else {
+            continue;
+        };
+
+        match replaced {
+            Some(replaced) if !other_bindings.contains(&replaced) => {
+                other_bindings[index] = replaced;
+            }
+            _ => {
+                other_bindings.remove(index);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_bound_once<T: std::fmt::Debug>(bindings: &BTreeMap<InputMapAction, Vec<T>>) {
+        let mut inputs: Vec<String> = bindings
+            .values()
+            .flatten()
+            .map(|input| format!("{input:?}"))
+            .collect();
+        let bound = inputs.len();
+
+        inputs.sort();
+        inputs.dedup();
+
+        assert_eq!(inputs.len(), bound, "an input is bound to several actions");
+    }
+
+    fn assert_no_shared_bindings(input_map: &InputMap) {
+        assert_bound_once(&input_map.keyboard);
+        assert_bound_once(&input_map.gamepad);
+    }
+
+    #[test]
+    fn defaults_bind_every_action_once() {
+        let input_map = InputMap::default();
+
+        for action in InputMapAction::ALL {
+            assert!(
+                !input_map.keys(action).is_empty() || !input_map.buttons(action).is_empty(),
+                "{action:?} is unbound"
+            );
+        }
+
+        assert_no_shared_bindings(&input_map);
+    }
+
+    #[test]
+    fn input_map_survives_a_toml_round_trip() {
+        let mut input_map = InputMap::default();
+        input_map.bind_key(InputMapAction::Jump, KeyCode::KeyW);
+        input_map.bind_button(InputMapAction::Dash, GamepadButton::East);
+
+        let toml = toml::to_string_pretty(&input_map).unwrap();
+
+        assert_eq!(toml::from_str::<InputMap>(&toml).unwrap(), input_map);
+    }
+
+    #[test]
+    fn actions_missing_from_a_saved_map_get_their_defaults() {
+        let saved: InputMap = toml::from_str(
+            r#"
+            [keyboard]
+            jump = ["KeyW"]
+
+            [gamepad]
+            jump = ["North"]
+            "#,
+        )
+        .unwrap();
+        let input_map = saved.with_default_bindings_for_new_actions();
+
+        assert_eq!(input_map.keys(InputMapAction::Jump), &[KeyCode::KeyW]);
+        assert_eq!(
+            input_map.keys(InputMapAction::Attack),
+            InputMap::default().keys(InputMapAction::Attack)
+        );
+    }
+
+    #[test]
+    fn rebinding_keeps_other_bindings_of_the_action() {
+        let mut input_map = InputMap::default();
+
+        input_map.bind_key(InputMapAction::MoveLeft, KeyCode::KeyJ);
+
+        assert_eq!(
+            input_map.keys(InputMapAction::MoveLeft),
+            &[KeyCode::KeyJ, KeyCode::KeyA]
+        );
+    }
+
+    #[test]
+    fn rebinding_a_taken_key_swaps_it_with_the_other_action() {
+        let mut input_map = InputMap::default();
+
+        input_map.bind_key(InputMapAction::Attack, KeyCode::Space);
+
+        assert_eq!(input_map.keys(InputMapAction::Attack), &[KeyCode::Space]);
+        assert_eq!(input_map.keys(InputMapAction::Jump), &[KeyCode::KeyF]);
+        assert_no_shared_bindings(&input_map);
+
+        input_map.bind_button(InputMapAction::Parry, GamepadButton::South);
+
+        assert_eq!(
+            input_map.buttons(InputMapAction::Parry),
+            &[GamepadButton::South]
+        );
+        assert_eq!(
+            input_map.buttons(InputMapAction::Jump),
+            &[GamepadButton::LeftTrigger]
+        );
+        assert_no_shared_bindings(&input_map);
+    }
+
+    #[test]
+    fn rebinding_an_unbound_action_takes_the_key_away_from_the_other_action() {
+        let mut input_map = InputMap::default();
+
+        input_map.bind_key(InputMapAction::RollLeft, KeyCode::ControlLeft);
+
+        assert_eq!(
+            input_map.keys(InputMapAction::RollLeft),
+            &[KeyCode::ControlLeft]
+        );
+        assert!(input_map.keys(InputMapAction::Roll).is_empty());
+        assert_no_shared_bindings(&input_map);
+    }
+}
@@ -1,5 +1,12 @@
 use crate::input_systems::gamepad_input::{gamepad_input_system, GamepadInputStates};
+use crate::input_systems::input_map::InputMap;
+use crate::input_systems::input_replay::{
+    input_playback_system, input_recording_exit_system, input_recording_system, input_tick_system,
+    is_replaying_input, record_replay_start_system, start_input_replay_system, InputReplayMode,
+    InputTick,
+};
 use crate::input_systems::keyboard_input_system::keyboard_input_system;
+use crate::player_systems::player_control_system::player_control_system;
 use crate::GameStates;
 use bevy::prelude::*;
 
@@ -7,13 +14,32 @@ pub struct InputPlugin;
 
 impl Plugin for InputPlugin {
     fn build(&self, app: &mut App) {
+        // Headless runs insert their own map, instead of the one of whoever runs them
+        if !app.world().contains_resource::<InputMap>() {
+            app.insert_resource(InputMap::load());
+        }
+
         app.insert_resource(GamepadInputStates::default())
+            .init_resource::<InputReplayMode>()
+            .init_resource::<InputTick>()
             // input systems must run in Update, since just_pressed* functions
             // get cleared each frame
             .add_systems(
                 Update,
                 (gamepad_input_system, keyboard_input_system)
+                    .run_if(in_state(GameStates::GameLoop))
+                    .run_if(not(is_replaying_input)),
+            )
+            .add_systems(Startup, start_input_replay_system)
+            .add_systems(OnExit(GameStates::MainMenu), record_replay_start_system)
+            // replayed input goes through the same events, in the tick it was recorded in
+            .add_systems(
+                FixedUpdate,
+                (input_tick_system, input_playback_system, input_recording_system)
+                    .chain()
+                    .before(player_control_system)
                     .run_if(in_state(GameStates::GameLoop)),
-            );
+            )
+            .add_systems(Last, input_recording_exit_system);
     }
 }
@@ -0,0 +1,355 @@
+use crate::input_systems::PlayerInputAction;
+use crate::save_game::save_file::SavedPlayer;
+use crate::save_game::LoadedSave;
+use crate::scripting::scripted_game_entity::{GameData, GameState};
+use bevy::prelude::*;
+use bevy_ecs_ldtk::prelude::LevelIndices;
+use bevy_ecs_ldtk::LevelSelection;
+use bevy_prng::ChaCha8Rng;
+use bevy_rand::prelude::GlobalEntropy;
+use rand_core::{OsRng, RngCore};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Recordings are flushed to disk this often, so a crash still leaves a usable replay
+const RECORDING_FLUSH_TICKS: u64 = 256;
+
+/// Whether player input is recorded to, or played back from, a replay file
+#[derive(Resource, Default, Clone, Debug)]
+pub enum InputReplayMode {
+    #[default]
+    Off,
+    Record(PathBuf),
+    /// Keyboard and gamepad input is ignored while a replay plays
+    Replay(PathBuf),
+}
+
+impl InputReplayMode {
+    /// Picks the mode from `--record-input <file>` or `--replay-input <file>`
+    pub fn from_args(args: impl IntoIterator<Item = String>) -> Self {
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--record-input" => {
+                    return args.next().map(|path| Self::Record(path.into())).unwrap_or_default()
+                }
+                "--replay-input" => {
+                    return args.next().map(|path| Self::Replay(path.into())).unwrap_or_default()
+                }
+                _ => {}
+            }
+        }
+
+        Self::Off
+    }
+}
+
+/// A recorded session, the rng seed, the state the run started from
+/// and every input along with the tick it was handled in
+#[derive(Serialize, Deserialize, Default)]
+pub struct InputReplay {
+    pub seed: [u8; 32],
+    /// Missing until the recorded run leaves the main menu
+    #[serde(default)]
+    pub start: Option<ReplayStart>,
+    pub inputs: Vec<RecordedInput>,
+}
+
+/// What the main menu picked for the recorded run, a new game or one of the saves
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ReplayStart {
+    pub level_index: usize,
+    pub game_state: GameState,
+    /// The loaded save, applied once the player spawns
+    pub player: Option<SavedPlayer>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct RecordedInput {
+    pub tick: u64,
+    pub action: PlayerInputAction,
+}
+
+impl InputReplay {
+    pub fn read(path: &Path) -> anyhow::Result<Self> {
+        Ok(serde_json::from_slice(&fs::read(path)?)?)
+    }
+
+    pub fn write(&self, path: &Path) -> anyhow::Result<()> {
+        fs::write(path, serde_json::to_vec(self)?)?;
+
+        Ok(())
+    }
+}
+
+/// Fixed updates spent in the game loop. Loading takes a varying number of frames,
+/// so only ticks where the player can act are counted.
+#[derive(Resource, Default)]
+pub struct InputTick(pub u64);
+
+#[derive(Resource)]
+pub struct InputRecorder {
+    path: PathBuf,
+    replay: InputReplay,
+}
+
+#[derive(Resource)]
+pub struct InputPlayback {
+    replay: InputReplay,
+    next_input: usize,
+}
+
+impl InputRecorder {
+    /// Writes everything recorded so far to the replay file
+    pub fn flush(&self) {
+        if let Err(e) = self.replay.write(&self.path) {
+            error!(
+                "failed to write input recording {}: {e:?}",
+                self.path.display()
+            );
+        }
+    }
+}
+
+impl InputPlayback {
+    pub fn is_finished(&self) -> bool {
+        self.next_input >= self.replay.inputs.len()
+    }
+}
+
+pub fn is_replaying_input(mode: Res<InputReplayMode>) -> bool {
+    matches!(*mode, InputReplayMode::Replay(_))
+}
+
+/// Seeds the rng either from the replay, or with a fresh seed that gets recorded.
+/// Replays also restore the state their run started from, the main menu is skipped for them.
+pub fn start_input_replay_system(
+    mut commands: Commands,
+    mut mode: ResMut<InputReplayMode>,
+    mut rng: GlobalEntropy<ChaCha8Rng>,
+    game_data: Res<GameData>,
+    mut level_select: ResMut<LevelSelection>,
+) {
+    match mode.clone() {
+        InputReplayMode::Off => {}
+        InputReplayMode::Record(path) => {
+            let mut seed = [0; 32];
+            OsRng.fill_bytes(&mut seed);
+            rng.reseed(seed);
+
+            info!("recording input to {}", path.display());
+
+            commands.insert_resource(InputRecorder {
+                path,
+                replay: InputReplay {
+                    seed,
+                    start: None,
+                    inputs: vec![],
+                },
+            });
+        }
+        InputReplayMode::Replay(path) => match InputReplay::read(&path) {
+            Ok(replay) => {
+                rng.reseed(replay.seed);
+
+                info!(
+                    "replaying {} inputs from {}",
+                    replay.inputs.len(),
+                    path.display()
+                );
+
+                match &replay.start {
+                    Some(start) => {
+                        *game_data.game_state.lock().unwrap() = start.game_state.clone();
+                        *level_select = LevelSelection::index(start.level_index);
+
+                        if let Some(player) = &start.player {
+                            commands.insert_resource(LoadedSave(player.clone()));
+                        }
+                    }
+                    None => warn!(
+                        "input replay {} has no start state, replaying from a new game",
+                        path.display()
+                    ),
+                }
+
+                commands.insert_resource(InputPlayback {
+                    replay,
+                    next_input: 0,
+                });
+            }
+            Err(e) => {
+                error!("failed to read input replay {}: {e:?}", path.display());
+                *mode = InputReplayMode::Off;
+            }
+        },
+    }
+}
+
+/// Records what the run starts from, once the main menu has set it up
+pub fn record_replay_start_system(
+    recorder: Option<ResMut<InputRecorder>>,
+    game_data: Res<GameData>,
+    level_select: Res<LevelSelection>,
+    loaded_save: Option<Res<LoadedSave>>,
+) {
+    let Some(mut recorder) = recorder else {
+        return;
+    };
+
+    if recorder.replay.start.is_some() {
+        return;
+    }
+
+    let LevelSelection::Indices(LevelIndices { level, .. }) = *level_select else {
+        error!("the recorded run starts from a level without an index, it can't be replayed");
+        return;
+    };
+
+    recorder.replay.start = Some(ReplayStart {
+        level_index: level,
+        game_state: game_data.game_state.lock().unwrap().clone(),
+        player: loaded_save.map(|save| save.0.clone()),
+    });
+}
+
+pub fn input_tick_system(mut tick: ResMut<InputTick>) {
+    tick.0 += 1;
+}
+
+pub fn input_playback_system(
+    tick: Res<InputTick>,
+    playback: Option<ResMut<InputPlayback>>,
+    mut event_sender: EventWriter<PlayerInputAction>,
+) {
+    let Some(mut playback) = playback else {
+        return;
+    };
+
+    while let Some(input) = playback.replay.inputs.get(playback.next_input) {
+        if input.tick > tick.0 {
+            break;
+        }
+
+        event_sender.send(input.action.clone());
+        playback.next_input += 1;
+    }
+}
+
+pub fn input_recording_system(
+    tick: Res<InputTick>,
+    recorder: Option<ResMut<InputRecorder>>,
+    mut event_reader: EventReader<PlayerInputAction>,
+) {
+    let Some(mut recorder) = recorder else {
+        return;
+    };
+
+    for action in event_reader.read() {
+        recorder.replay.inputs.push(RecordedInput {
+            tick: tick.0,
+            action: action.clone(),
+        });
+    }
+
+    if tick.0 % RECORDING_FLUSH_TICKS == 0 {
+        recorder.flush();
+    }
+}
+
+pub fn input_recording_exit_system(
+    recorder: Option<Res<InputRecorder>>,
+    mut exit_reader: EventReader<AppExit>,
+) {
+    if let Some(recorder) = recorder {
+        if exit_reader.read().next().is_some() {
+            recorder.flush();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::movement_systems::movement_components::FacingDirection;
+    use crate::AttackDirection;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn mode_is_picked_from_the_arguments() {
+        assert!(matches!(
+            InputReplayMode::from_args(args(&["game", "--record-input", "run.json"])),
+            InputReplayMode::Record(path) if path == Path::new("run.json")
+        ));
+        assert!(matches!(
+            InputReplayMode::from_args(args(&["game", "-v", "--replay-input", "run.json"])),
+            InputReplayMode::Replay(path) if path == Path::new("run.json")
+        ));
+    }
+
+    #[test]
+    fn mode_is_off_without_arguments_or_a_file() {
+        assert!(matches!(
+            InputReplayMode::from_args(args(&["game"])),
+            InputReplayMode::Off
+        ));
+        assert!(matches!(
+            InputReplayMode::from_args(args(&["game", "--replay-input"])),
+            InputReplayMode::Off
+        ));
+    }
+
+    #[test]
+    fn replay_survives_a_round_trip_through_a_file() {
+        let path = std::env::temp_dir().join(format!(
+            "input-replay-round-trip-{}.json",
+            std::process::id()
+        ));
+        let replay = InputReplay {
+            seed: [7; 32],
+            start: Some(ReplayStart {
+                level_index: 4,
+                game_state: GameState::default(),
+                player: None,
+            }),
+            inputs: vec![
+                RecordedInput {
+                    tick: 3,
+                    action: PlayerInputAction::Horizontal(Vec2::new(-1., 0.5)),
+                },
+                RecordedInput {
+                    tick: 3,
+                    action: PlayerInputAction::Attack(AttackDirection::Sideways),
+                },
+                RecordedInput {
+                    tick: 40,
+                    action: PlayerInputAction::Roll(FacingDirection::West),
+                },
+            ],
+        };
+
+        replay.write(&path).unwrap();
+        let read = InputReplay::read(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(read.seed, replay.seed);
+        assert_eq!(read.start.map(|start| start.level_index), Some(4));
+        assert_eq!(
+            serde_json::to_value(&read.inputs).unwrap(),
+            serde_json::to_value(&replay.inputs).unwrap()
+        );
+    }
+
+    #[test]
+    fn replays_recorded_without_a_start_state_still_read() {
+        let replay: InputReplay =
+            serde_json::from_str(&format!(r#"{{"seed":{:?},"inputs":[]}}"#, [1; 32])).unwrap();
+
+        assert!(replay.start.is_none());
+    }
+}
@@ -1,3 +1,4 @@
+use crate::input_systems::input_map::{InputMap, InputMapAction};
 use crate::input_systems::PlayerInputAction;
 use crate::AttackDirection;
 use bevy::input::ButtonInput;
@@ -8,52 +9,62 @@ use crate::movement_systems::movement_components::FacingDirection;
 pub fn keyboard_input_system(
     mut event_sender: EventWriter<PlayerInputAction>,
     key_input: Res<ButtonInput<KeyCode>>,
+    input_map: Res<InputMap>,
 ) {
     let mut direction = Vec2::ZERO;
 
-    if key_input.all_pressed(vec![
-        KeyCode::ArrowLeft,
-        KeyCode::ControlLeft
-    ]) {
+    let pressed = |action| input_map.key_pressed(action, &key_input);
+    let just_pressed = |action| input_map.key_just_pressed(action, &key_input);
+
+    if pressed(InputMapAction::RollLeft)
+        || (pressed(InputMapAction::Roll) && pressed(InputMapAction::MoveLeft))
+    {
         event_sender.send(PlayerInputAction::Roll(FacingDirection::West));
         return;
     }
 
-    if key_input.all_pressed(vec![
-        KeyCode::ArrowRight,
-        KeyCode::ControlLeft
-    ]) {
+    if pressed(InputMapAction::RollRight)
+        || (pressed(InputMapAction::Roll) && pressed(InputMapAction::MoveRight))
+    {
         event_sender.send(PlayerInputAction::Roll(FacingDirection::East));
         return;
     }
 
-    if key_input.pressed(KeyCode::ArrowDown) && key_input.just_pressed(KeyCode::KeyF) {
+    if pressed(InputMapAction::Down) && just_pressed(InputMapAction::Attack) {
         event_sender.send(PlayerInputAction::Attack(AttackDirection::Down));
-    } else if key_input.just_pressed(KeyCode::KeyF) {
+    } else if just_pressed(InputMapAction::Attack) {
         event_sender.send(PlayerInputAction::Attack(AttackDirection::Sideways));
     }
 
-    if key_input.pressed(KeyCode::KeyD) || key_input.pressed(KeyCode::ArrowRight) {
+    if pressed(InputMapAction::MoveRight) {
         direction.x = 1.;
-    } else if key_input.pressed(KeyCode::KeyA) || key_input.pressed(KeyCode::ArrowLeft) {
+    } else if pressed(InputMapAction::MoveLeft) {
         direction.x = -1.;
     }
 
-    if key_input.just_pressed(KeyCode::ArrowUp) {
+    if just_pressed(InputMapAction::Interact) {
         event_sender.send(PlayerInputAction::Interact);
     }
 
+    if just_pressed(InputMapAction::Dash) {
+        event_sender.send(PlayerInputAction::Dash);
+    }
+
+    if just_pressed(InputMapAction::Parry) {
+        event_sender.send(PlayerInputAction::Parry);
+    }
+
     if direction.length() > 0.1 {
         event_sender.send(PlayerInputAction::Horizontal(direction));
     }
 
-    if key_input.just_pressed(KeyCode::Space) {
+    if just_pressed(InputMapAction::Jump) {
         event_sender.send(PlayerInputAction::JumpStart);
-    } else if key_input.pressed(KeyCode::Space) {
+    } else if pressed(InputMapAction::Jump) {
         event_sender.send(PlayerInputAction::Jump);
     }
 
-    if key_input.just_released(KeyCode::Space) {
+    if input_map.key_just_released(InputMapAction::Jump, &key_input) {
         event_sender.send(PlayerInputAction::JumpAbort);
     }
     
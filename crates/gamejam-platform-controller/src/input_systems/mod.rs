@@ -1,13 +1,16 @@
 use crate::AttackDirection;
 use bevy::math::Vec2;
 use bevy::prelude::Event;
+use serde::{Deserialize, Serialize};
 use crate::movement_systems::movement_components::FacingDirection;
 
 pub mod gamepad_input;
+pub mod input_map;
+pub mod input_replay;
 pub mod keyboard_input_system;
 pub mod input_plugin;
 
-#[derive(Event)]
+#[derive(Event, Clone, Serialize, Deserialize)]
 pub enum PlayerInputAction {
     Horizontal(Vec2),
     Jump,
@@ -17,5 +20,7 @@ pub enum PlayerInputAction {
     Interact,
     ReloadLevel,
     Roll(FacingDirection),
+    Dash,
+    Parry,
     GoToBoss
 }
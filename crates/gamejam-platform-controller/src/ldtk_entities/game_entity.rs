@@ -1,29 +1,72 @@
+use crate::combat::combat_components::{HitInvulnerability, Resistances};
+use crate::combat::knockback::WeightClass;
 use crate::game_entities::file_formats::game_entity_definitions::{
-    GameEntityDefinitionFile, GameEntityDefinitionFileHandle,
+    AnimationDescription, GameEntityDefinition, GameEntityDefinitionFile,
+    GameEntityDefinitionFileHandle,
 };
+use crate::graphics::sprite_collection::SpriteCollection;
+use crate::ldtk_entities::trigger_channels::resolve_trigger_channels;
 use crate::ldtk_entities::{get_ldtk_string_array_field, get_ldtk_string_field};
 use crate::scripting::create_entity_script::create_entity_script;
 use crate::scripting::scripted_game_entity::{GameData, GameEntityHostLinker};
 use crate::timing::timing_component::TimerComponent;
 use bevy::prelude::*;
 use bevy_ecs_ldtk::EntityInstance;
-use bevy_wasmer_scripting::scripted_entity::WasmEngine;
-use bevy_wasmer_scripting::wasm_script_asset::WasmScriptModuleBytes;
+use bevy_wasmer_scripting::component_cache::ComponentCache;
+use bevy_wasmer_scripting::scripted_entity::{ScriptFuelBudget, WasmEngine};
+use bevy_wasmer_scripting::wasm_script_asset::{CompiledComponents, WasmScriptModuleBytes};
+use std::time::Duration;
+
+/// What a game entity is made of, resolved from an `entities.toml` prototype or LDtk fields
+pub struct GameEntitySpawn {
+    pub script_path: Option<String>,
+    pub prototype_name: Option<String>,
+    /// LDtk iid, entities spawned at runtime have no persistent state
+    pub state_key: Option<String>,
+    pub script_params: Vec<String>,
+    pub z: Option<f32>,
+    pub fuel_per_call: Option<u64>,
+    pub animation: Option<AnimationDescription>,
+    pub resistances: Option<Resistances>,
+    pub hit_invulnerability: Option<HitInvulnerability>,
+    pub weight_class: Option<WeightClass>,
+}
+
+impl GameEntitySpawn {
+    pub fn from_prototype(prototype_name: &str, prototype: &GameEntityDefinition) -> Self {
+        Self {
+            script_path: prototype.script_path.clone(),
+            prototype_name: Some(prototype_name.to_string()),
+            state_key: None,
+            script_params: prototype.script_params.clone().unwrap_or_default(),
+            z: prototype.z,
+            fuel_per_call: prototype.fuel_per_call,
+            animation: prototype.animation.clone(),
+            resistances: prototype.resistances.clone(),
+            hit_invulnerability: prototype.hit_invulnerability.clone(),
+            weight_class: prototype.weight_class,
+        }
+    }
+}
 
 pub fn game_entity_try_from_entity_instance(
-    entity: Entity,
+    entity_commands: &mut EntityCommands,
     entity_db: &Res<Assets<GameEntityDefinitionFile>>,
     entity_db_handle: &Res<GameEntityDefinitionFileHandle>,
     entity_instance: &EntityInstance,
+    sprites: &SpriteCollection,
     engine: &Res<WasmEngine>,
+    component_cache: Option<&ComponentCache>,
+    fuel_budget: &Res<ScriptFuelBudget>,
     linker: &mut GameEntityHostLinker,
     game_data: &Res<GameData>,
     asset_server: &Res<AssetServer>,
-    wasm_scripts: &mut ResMut<Assets<WasmScriptModuleBytes>>,
+    wasm_scripts: &Assets<WasmScriptModuleBytes>,
+    compiled_components: &mut CompiledComponents,
     mut transform: Transform,
-) -> Option<(impl Bundle, Option<impl Bundle>)> {
+) {
     if entity_instance.identifier != "game_entity" {
-        return None;
+        return;
     }
 
     let db = entity_db
@@ -32,53 +75,132 @@ pub fn game_entity_try_from_entity_instance(
 
     let prototype_name = get_ldtk_string_field("prototype_name", &entity_instance);
 
-    let script = match prototype_name {
+    let mut spawn = match prototype_name {
         Some(prototype_name) => {
             let prototype = db
                 .entities
                 .get(&prototype_name)
                 .expect(&format!("missing entity prototype {prototype_name}"));
 
-            prototype
-                .script_path
-                .clone()
-                .map(|path| (path, prototype.script_params.clone(), prototype.z))
+            GameEntitySpawn::from_prototype(&prototype_name, prototype)
         }
-        _ => Some((
-            get_ldtk_string_field("script_file", &entity_instance).expect("missing script file"),
-            None,
-            None,
-        )),
+        _ => GameEntitySpawn {
+            script_path: Some(
+                get_ldtk_string_field("script_file", &entity_instance)
+                    .expect("missing script file"),
+            ),
+            prototype_name: None,
+            state_key: None,
+            script_params: vec![],
+            z: None,
+            fuel_per_call: None,
+            animation: None,
+            resistances: None,
+            hit_invulnerability: None,
+            weight_class: None,
+        },
     };
 
-    let script = script.map(|(path, script_params, z)| {
-        let mut script_params = script_params.unwrap_or(vec![]);
+    spawn.script_params.append(
+        &mut get_ldtk_string_array_field("script_params", &entity_instance).unwrap_or(vec![]),
+    );
+    spawn.script_params = resolve_trigger_channels(spawn.script_params, &entity_instance);
+    spawn.state_key = Some(entity_instance.iid.clone());
 
-        script_params.append(
-            &mut get_ldtk_string_array_field("script_params", &entity_instance).unwrap_or(vec![]),
-        );
+    transform.scale = Vec3::splat(1.);
 
-        transform.translation.z = z.unwrap_or(transform.translation.z);
+    spawn_game_entity(
+        entity_commands,
+        spawn,
+        sprites,
+        engine,
+        component_cache,
+        fuel_budget,
+        linker,
+        game_data,
+        asset_server.as_ref(),
+        wasm_scripts,
+        compiled_components,
+        transform,
+    );
+}
 
-        create_entity_script(
-            entity,
-            &path,
-            &engine,
-            linker,
-            asset_server.as_ref(),
-            game_data,
-            wasm_scripts.as_mut(),
-            Some(script_params),
-            transform.translation.xy(),
-        )
-    });
+/// Turns the entity into a game entity, with a script and/or an animation.
+/// Shared by entities placed in LDtk and entities spawned by scripts.
+pub fn spawn_game_entity(
+    entity_commands: &mut EntityCommands,
+    spawn: GameEntitySpawn,
+    sprites: &SpriteCollection,
+    engine: &Res<WasmEngine>,
+    component_cache: Option<&ComponentCache>,
+    fuel_budget: &Res<ScriptFuelBudget>,
+    linker: &mut GameEntityHostLinker,
+    game_data: &Res<GameData>,
+    asset_server: &AssetServer,
+    wasm_scripts: &Assets<WasmScriptModuleBytes>,
+    compiled_components: &mut CompiledComponents,
+    mut transform: Transform,
+) {
+    let entity = entity_commands.id();
 
-    transform.scale = Vec3::splat(1.);
+    transform.translation.z = spawn.z.unwrap_or(transform.translation.z);
+
+    entity_commands.insert((transform, GameEntity {}, TimerComponent::default()));
 
-    Some((
-        (transform, GameEntity {}, TimerComponent::default()),
-        script,
-    ))
+    if let Some(animation) = spawn.animation {
+        if let Some(bundle) = sprites.create_sprite_animation_bundle(
+            &animation.sprite_name,
+            &animation.animation_name,
+            Duration::from_millis(animation.duration_millis),
+            true,
+            false,
+            false,
+        ) {
+            entity_commands.insert(bundle);
+        }
+    }
+
+    if let Some(resistances) = spawn.resistances {
+        entity_commands.insert(resistances);
+    }
+
+    if let Some(hit_invulnerability) = spawn.hit_invulnerability {
+        entity_commands.insert(hit_invulnerability);
+    }
+
+    if let Some(weight_class) = spawn.weight_class {
+        entity_commands.insert(weight_class);
+    }
+
+    let Some(path) = spawn.script_path else {
+        return;
+    };
+
+    let script = create_entity_script(
+        entity,
+        &path,
+        spawn.prototype_name,
+        spawn.state_key,
+        engine,
+        component_cache,
+        linker,
+        asset_server,
+        game_data,
+        wasm_scripts,
+        compiled_components,
+        Some(spawn.script_params),
+        transform.translation.xy(),
+        spawn.fuel_per_call.unwrap_or(fuel_budget.0),
+    );
+
+    match script {
+        Ok(script) => {
+            entity_commands.insert(script);
+        }
+        Err(e) => {
+            error!("failed to create script {path} for entity {entity}: {e:?}");
+        }
+    }
 }
 
 /// General purpose game entity
@@ -5,6 +5,7 @@ use crate::game_entities::file_formats::game_entity_definitions::{
 use crate::ldtk_entities::chest::{
     chest_animation_completed_observer, chest_opening_added_observer, spawn_chest_system, Chest,
 };
+use crate::graphics::sprite_collection::SpriteCollection;
 use crate::ldtk_entities::game_entity::game_entity_try_from_entity_instance;
 use crate::ldtk_entities::interactable::interactable_player_system;
 use crate::ldtk_entities::level_transition::{
@@ -22,8 +23,9 @@ use bevy::prelude::*;
 use bevy_ecs_ldtk::app::LdtkEntityAppExt;
 use bevy_ecs_ldtk::ldtk::{FieldInstance, FieldValue};
 use bevy_ecs_ldtk::EntityInstance;
-use bevy_wasmer_scripting::scripted_entity::WasmEngine;
-use bevy_wasmer_scripting::wasm_script_asset::WasmScriptModuleBytes;
+use bevy_wasmer_scripting::component_cache::ComponentCache;
+use bevy_wasmer_scripting::scripted_entity::{ScriptFuelBudget, WasmEngine};
+use bevy_wasmer_scripting::wasm_script_asset::{CompiledComponents, WasmScriptModuleBytes};
 use std::time::Duration;
 
 pub mod chest;
@@ -33,6 +35,7 @@ pub mod level_transition;
 pub mod player_collidable_entity;
 pub mod player_spawn;
 pub mod rubble;
+pub mod trigger_channels;
 
 pub struct GameLdtkEntitiesPlugin;
 
@@ -68,12 +71,16 @@ impl Plugin for GameLdtkEntitiesPlugin {
 pub fn handle_ldtk_entities_spawn(
     mut commands: Commands,
     engine: Res<WasmEngine>,
+    component_cache: Option<Res<ComponentCache>>,
+    fuel_budget: Res<ScriptFuelBudget>,
     mut linker: ResMut<GameEntityHostLinker>,
     asset_server: Res<AssetServer>,
     game_data: Res<GameData>,
-    mut wasm_scripts: ResMut<Assets<WasmScriptModuleBytes>>,
+    wasm_scripts: Res<Assets<WasmScriptModuleBytes>>,
+    mut compiled_components: ResMut<CompiledComponents>,
     entity_db: Res<Assets<GameEntityDefinitionFile>>,
     entity_db_handle: Res<GameEntityDefinitionFileHandle>,
+    sprites: Res<SpriteCollection>,
     entities: Query<(Entity, &EntityInstance, &Transform), Added<EntityInstance>>,
 ) {
     for (entity, entity_instance, transform) in entities.iter() {
@@ -117,26 +124,22 @@ pub fn handle_ldtk_entities_spawn(
             }
             "game_entity" => {
                 info!("Game entity spawned");
-                let Some((bundle, script)) = game_entity_try_from_entity_instance(
-                    entity,
+                game_entity_try_from_entity_instance(
+                    &mut commands.entity(entity),
                     &entity_db,
                     &entity_db_handle,
                     entity_instance,
+                    &sprites,
                     &engine,
+                    component_cache.as_deref(),
+                    &fuel_budget,
                     linker.as_mut(),
                     &game_data,
                     &asset_server,
-                    &mut wasm_scripts,
+                    &wasm_scripts,
+                    compiled_components.as_mut(),
                     *transform,
-                ) else {
-                    continue;
-                };
-
-                commands.entity(entity).insert(bundle);
-
-                if let Some(script) = script {
-                    commands.entity(entity).insert(script);
-                }
+                );
             }
             "level_transition" => {
                 info!("level transition spawned");
@@ -269,3 +272,28 @@ pub fn get_ldtk_string_array_field(
 
     None
 }
+
+/// Iids of the entities referenced by an entity reference field, single or array
+pub fn get_ldtk_entity_ref_array_field(
+    key: &str,
+    entity_instance: &EntityInstance,
+) -> Option<Vec<String>> {
+    for field in &entity_instance.field_instances {
+        if field.identifier != key {
+            continue;
+        }
+
+        return match &field.value {
+            FieldValue::EntityRef(v) => Some(v.iter().map(|v| v.entity_iid.clone()).collect()),
+            FieldValue::EntityRefs(v) => Some(
+                v.iter()
+                    .flatten()
+                    .map(|v| v.entity_iid.clone())
+                    .collect(),
+            ),
+            _ => None,
+        };
+    }
+
+    None
+}
@@ -1,3 +1,4 @@
+use crate::player_systems::input_buffer::InputBuffer;
 use crate::player_systems::player_components::Player;
 use bevy::prelude::*;
 
@@ -11,13 +12,25 @@ pub struct RequestedPlayerSpawn {
     pub spawn_name: String,
 }
 
+/// Places the player at the requested spawn, dropping presses buffered before the death,
+/// reload or level transition that requested it
 pub fn move_player_to_spawn(
     mut commands: Commands,
-    mut player_query: Query<(Entity, &mut Transform, &RequestedPlayerSpawn), With<Player>>,
+    mut player_query: Query<
+        (
+            Entity,
+            &mut Transform,
+            &mut InputBuffer,
+            &RequestedPlayerSpawn,
+        ),
+        With<Player>,
+    >,
     mut camera_query: Query<&mut Transform, (With<Camera2d>, Without<Player>)>,
     spawn_query: Query<(&Transform, &PlayerSpawnEntity), (Without<Player>, Without<Camera2d>)>,
 ) {
-    let Ok((player_entity, mut player_transform, request)) = player_query.get_single_mut() else {
+    let Ok((player_entity, mut player_transform, mut input_buffer, request)) =
+        player_query.get_single_mut()
+    else {
         return;
     };
 
@@ -32,6 +45,7 @@ pub fn move_player_to_spawn(
             player_transform.translation.y = transform.translation.y;
             camera_transform.translation.x = player_transform.translation.x;
             camera_transform.translation.y = player_transform.translation.y;
+            input_buffer.clear();
 
             commands
                 .entity(player_entity)
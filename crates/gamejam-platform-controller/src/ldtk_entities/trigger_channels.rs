@@ -0,0 +1,186 @@
+use crate::ldtk_entities::get_ldtk_entity_ref_array_field;
+use bevy_ecs_ldtk::EntityInstance;
+
+/// Channel a trigger target (door, platform, ...) listens on, trigger sources publish to it too
+const TRIGGER_ID_PARAM: &str = "trigger-id";
+/// Channel named after the LDtk iid, which references to the entity resolve to.
+/// Targets listen on it next to their `trigger-id`, sources never publish to it.
+const TRIGGER_LISTEN_PARAM: &str = "trigger-listen";
+/// Channels a trigger source (lever, powerup, ...) publishes to
+const TRIGGER_TARGETS_PARAM: &str = "trigger-targets";
+/// LDtk entity reference field, lets a trigger source point straight at its targets
+const TRIGGER_TARGETS_FIELD: &str = "trigger_targets";
+
+/// Stable id of a named trigger channel.
+/// Named channels live in the upper half of the id space, so they never clash with numeric ids.
+pub fn trigger_channel_id(name: &str) -> u32 {
+    // FNV-1a, ids have to be the same on every run and every platform
+    let hash = name.bytes().fold(0x811c9dc5u32, |hash, byte| {
+        (hash ^ byte as u32).wrapping_mul(0x01000193)
+    });
+
+    hash | 0x8000_0000
+}
+
+fn resolve_channel(channel: &str) -> u32 {
+    channel
+        .parse()
+        .unwrap_or_else(|_| trigger_channel_id(channel))
+}
+
+/// Rewrites named trigger channels in `script_params` to their ids and adds the entities
+/// referenced by the `trigger_targets` field as targets.
+/// Every entity gets a `trigger-listen` channel named after its LDtk iid,
+/// which is what references to it resolve to.
+pub fn resolve_trigger_channels(
+    script_params: Vec<String>,
+    entity_instance: &EntityInstance,
+) -> Vec<String> {
+    let mut resolved = Vec::with_capacity(script_params.len());
+    let mut trigger_targets = vec![];
+
+    for param in script_params {
+        match param.split_once('=') {
+            Some((TRIGGER_ID_PARAM, channel)) => {
+                resolved.push(format!("{TRIGGER_ID_PARAM}={}", resolve_channel(channel)));
+            }
+            Some((TRIGGER_TARGETS_PARAM, channels)) => {
+                trigger_targets.extend(
+                    channels
+                        .split(',')
+                        .filter(|channel| !channel.is_empty())
+                        .map(resolve_channel),
+                );
+            }
+            _ => resolved.push(param),
+        }
+    }
+
+    if let Some(iids) = get_ldtk_entity_ref_array_field(TRIGGER_TARGETS_FIELD, entity_instance) {
+        trigger_targets.extend(iids.iter().map(|iid| trigger_channel_id(iid)));
+    }
+
+    if !trigger_targets.is_empty() {
+        let trigger_targets = trigger_targets
+            .iter()
+            .map(u32::to_string)
+            .collect::<Vec<_>>()
+            .join(",");
+
+        resolved.push(format!("{TRIGGER_TARGETS_PARAM}={trigger_targets}"));
+    }
+
+    resolved.push(format!(
+        "{TRIGGER_LISTEN_PARAM}={}",
+        trigger_channel_id(&entity_instance.iid)
+    ));
+
+    resolved
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_ecs_ldtk::ldtk::FieldInstance;
+
+    fn entity_instance(iid: &str, trigger_targets: &[&str]) -> EntityInstance {
+        let entity_refs: Vec<_> = trigger_targets
+            .iter()
+            .map(|iid| {
+                serde_json::json!({
+                    "entityIid": iid,
+                    "layerIid": "layer",
+                    "levelIid": "level",
+                    "worldIid": "world",
+                })
+            })
+            .collect();
+
+        let field: FieldInstance = serde_json::from_value(serde_json::json!({
+            "__identifier": TRIGGER_TARGETS_FIELD,
+            "__type": "Array<EntityRef>",
+            "__value": entity_refs,
+            "__tile": null,
+            "defUid": 1,
+            "realEditorValues": [],
+        }))
+        .unwrap();
+
+        EntityInstance {
+            iid: iid.to_string(),
+            field_instances: vec![field],
+            ..Default::default()
+        }
+    }
+
+    fn params(params: &[&str]) -> Vec<String> {
+        params.iter().map(|param| param.to_string()).collect()
+    }
+
+    #[test]
+    fn channel_ids_are_stable_and_stay_clear_of_numeric_ids() {
+        assert_eq!(trigger_channel_id("door"), trigger_channel_id("door"));
+        assert_ne!(trigger_channel_id("door"), trigger_channel_id("gate"));
+        // FNV-1a of "door" with the top bit set, the same on every run and platform
+        assert_eq!(trigger_channel_id("door"), 0xa1f5_b729);
+        assert!(trigger_channel_id("") >= 0x8000_0000);
+    }
+
+    #[test]
+    fn named_and_numeric_channels_resolve() {
+        let resolved = resolve_trigger_channels(
+            params(&[
+                "trigger-id=vault",
+                "trigger-targets=3,vault,",
+                "sprite=door",
+            ]),
+            &entity_instance("self", &[]),
+        );
+
+        let vault = trigger_channel_id("vault");
+
+        assert_eq!(
+            resolved,
+            vec![
+                format!("trigger-id={vault}"),
+                "sprite=door".to_string(),
+                format!("trigger-targets=3,{vault}"),
+                format!("trigger-listen={}", trigger_channel_id("self")),
+            ]
+        );
+    }
+
+    #[test]
+    fn references_target_the_listen_channel_of_the_referenced_entity() {
+        let resolved = resolve_trigger_channels(
+            params(&["trigger-targets=2"]),
+            &entity_instance("lever", &["door-a", "door-b"]),
+        );
+
+        assert_eq!(
+            resolved,
+            vec![
+                format!(
+                    "trigger-targets=2,{},{}",
+                    trigger_channel_id("door-a"),
+                    trigger_channel_id("door-b")
+                ),
+                format!("trigger-listen={}", trigger_channel_id("lever")),
+            ]
+        );
+    }
+
+    #[test]
+    fn an_existing_trigger_id_is_kept_next_to_the_listen_channel() {
+        let resolved =
+            resolve_trigger_channels(params(&["trigger-id=7"]), &entity_instance("door", &[]));
+
+        assert_eq!(
+            resolved,
+            vec![
+                "trigger-id=7".to_string(),
+                format!("trigger-listen={}", trigger_channel_id("door")),
+            ]
+        );
+    }
+}
@@ -1 +1,2 @@
 pub mod levels_plugin;
+pub mod world_plugin;
@@ -3,6 +3,7 @@ use crate::combat::EnemyPlugin;
 use crate::game_entities::file_formats::game_entity_definitions::GameEntityDefinitionFile;
 use crate::game_resources::{load_resources, load_scripts_system};
 use crate::graphics::animation_system::animated_sprite_system;
+use crate::graphics::flicker_system::{flicker_system, flickering_removed_observer};
 use crate::graphics::materials::fog_material::FogMaterial;
 use crate::graphics::sprite_collection::{
     spawn_sprite_collection_system, AnimatedSpriteFile, SpriteCollection,
@@ -13,12 +14,16 @@ use crate::input_systems::keyboard_input_system::keyboard_input_system;
 use crate::ldtk_entities::GameLdtkEntitiesPlugin;
 use crate::main_menu::main_menu_plugin::MainMenuPlugin;
 use crate::movement_systems::movement_plugin::MovementPlugin;
-use crate::player_systems::player_attack_system::{player_attack_start_system, player_pogo_system};
+use crate::player_systems::player_attack_system::{
+    player_attack_start_system, player_hit_landed_observer, player_pogo_system,
+};
 use crate::player_systems::player_control_system::player_control_system;
 use crate::player_systems::player_health::player_health_sync_system;
 use crate::player_systems::player_spawn_system::{
     spawn_player_system, spawn_player_ui_proxy_system,
 };
+use crate::save_game::save_file::user_game_data_directory;
+use crate::save_game::SaveGamePlugin;
 use crate::scripting::ScriptedGameEntityPlugin;
 use crate::timing::timer_system::timer_system;
 use crate::ui::game_ui::setup_game_ui;
@@ -30,12 +35,14 @@ use bevy_common_assets::toml::TomlAssetPlugin;
 use bevy_ecs_ldtk::prelude::*;
 use bevy_framepace::{FramepaceSettings, Limiter};
 use bevy_rand::plugin::EntropyPlugin;
+use bevy_wasmer_scripting::component_cache::ComponentCache;
 use bevy_wasmer_scripting::wasm_script_asset::{
     WasmScriptModuleBytes, WasmScriptModuleBytesLoader,
 };
 use bevy_wasmer_scripting::WasmtimeScriptPlugin;
 use gamejam_bevy_components::Interactable;
 use haalka::HaalkaPlugin;
+use serde::{Deserialize, Serialize};
 use input_systems::PlayerInputAction;
 use simple_2d_camera::PixelCameraResolution;
 use std::time::Duration;
@@ -46,12 +53,14 @@ pub mod combat;
 pub mod game_entities;
 pub mod game_resources;
 pub mod graphics;
-mod input_systems;
+pub mod headless;
+pub mod input_systems;
 pub mod ldtk_entities;
 pub mod main_menu;
 pub mod movement_systems;
 mod player_const_rules;
 pub mod player_systems;
+pub mod save_game;
 pub mod scripting;
 pub mod timing;
 pub mod ui;
@@ -69,6 +78,7 @@ pub enum GameStates {
     LoadLevel
 }
 
+/// The full game, gameplay along with its rendering, audio and menus
 pub struct PlatformerPlugin;
 
 impl Plugin for PlatformerPlugin {
@@ -77,22 +87,39 @@ impl Plugin for PlatformerPlugin {
             //shaders and stuff
             .add_plugins(Material2dPlugin::<FogMaterial>::default())
             // other stuff
-            .init_state::<GameStates>()
+            .add_plugins(GameAudioPlugin)
+            .add_plugins(MainMenuPlugin {})
+            .add_plugins(HaalkaPlugin)
+            .add_plugins(PlatformerGameplayPlugin)
+            .add_systems(OnEnter(GameStates::MainMenu), spawn_fog_system)
+            .add_systems(OnEnter(GameStates::SpawnPlayer), setup_game_ui);
+    }
+}
+
+/// Everything that simulates the game, without anything that needs a window, GPU or audio device
+pub struct PlatformerGameplayPlugin;
+
+impl Plugin for PlatformerGameplayPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_state::<GameStates>()
             .insert_resource(LdtkSettings {
                 level_background: LevelBackground::Nonexistent,
                 ..default()
             })
             .add_plugins(EntropyPlugin::<bevy_rand::prelude::ChaCha8Rng>::default())
-            .add_plugins(GameAudioPlugin)
             .add_plugins(LevelsPlugin)
             .add_plugins(InputPlugin)
-            .add_plugins(MainMenuPlugin {})
             .add_plugins(GameLdtkEntitiesPlugin)
-            .add_plugins(WasmtimeScriptPlugin)
+            .add_plugins(WasmtimeScriptPlugin {
+                // Cached components are loaded as native code, never keep them in a shared directory
+                component_cache: user_game_data_directory()
+                    .map(|directory| ComponentCache::new(directory.join("script-cache"))),
+                ..default()
+            })
             .add_plugins(EnemyPlugin)
             .add_plugins(MovementPlugin)
-            .add_plugins(HaalkaPlugin)
             .add_plugins(ScriptedGameEntityPlugin)
+            .add_plugins(SaveGamePlugin)
             .add_systems(Startup, (load_resources, spawn_player_ui_proxy_system))
             .init_asset::<WasmScriptModuleBytes>()
             .init_asset_loader::<WasmScriptModuleBytesLoader>()
@@ -113,16 +140,15 @@ impl Plugin for PlatformerPlugin {
                     .continue_to_state(GameStates::MainMenu)
                     .load_collection::<PlayerAssets>(),
             )
-            .add_systems(OnEnter(GameStates::MainMenu), spawn_fog_system)
-            .add_systems(
-                OnEnter(GameStates::SpawnPlayer),
-                (spawn_player_system, setup_game_ui),
-            )
+            .add_systems(OnEnter(GameStates::SpawnPlayer), spawn_player_system)
+            .add_observer(player_hit_landed_observer)
+            .add_observer(flickering_removed_observer)
             .add_event::<PlayerInputAction>()
             .add_systems(
                 FixedUpdate,
                 (
                     animated_sprite_system,
+                    flicker_system,
                     player_control_system,
                     player_attack_start_system,
                     player_pogo_system,
@@ -253,7 +279,7 @@ fn spawn_terminal_system(
     }
 }
 
-#[derive(Copy, Clone, Eq, PartialEq)]
+#[derive(Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
 pub enum AttackDirection {
     Down,
     Sideways,
@@ -1,3 +1,5 @@
+use crate::input_systems::input_map::{InputMap, InputMapAction};
+use crate::save_game::SaveSlots;
 use bevy::prelude::*;
 use haalka::prelude::{Mutable, MutableVec, ReadOnlyMutable};
 
@@ -7,6 +9,10 @@ pub struct MainMenuComponent {
     pub selected_index: Mutable<usize>,
 }
 
+/// Action waiting for the next key or button press on the controls page
+#[derive(Resource, Default)]
+pub struct PendingRebind(pub Option<InputMapAction>);
+
 #[derive(Resource, Default)]
 pub struct UiAudioLevels {
     pub global: Mutable<f32>,
@@ -14,10 +20,10 @@ pub struct UiAudioLevels {
     pub effects: Mutable<f32>,
 }
 
-impl Default for MainMenuComponent {
-    fn default() -> Self {
+impl MainMenuComponent {
+    pub fn new(save_slots: &SaveSlots) -> Self {
         Self {
-            entries: MutableVec::new_with_values(MenuEntry::default_menu()),
+            entries: MutableVec::new_with_values(MenuEntry::default_menu(save_slots)),
             selected_index: Default::default(),
         }
     }
@@ -36,13 +42,38 @@ impl MenuEntry {
         ]
     }
 
-    pub fn default_menu() -> Vec<Self> {
-        vec![
-            MenuEntry::StartGame,
-            MenuEntry::Settings,
-            MenuEntry::Attributions,
-            MenuEntry::Quit,
-        ]
+    pub fn default_menu(save_slots: &SaveSlots) -> Vec<Self> {
+        let mut entries = vec![];
+
+        if save_slots.latest().is_some() {
+            entries.push(MenuEntry::Continue);
+        }
+
+        entries.push(MenuEntry::StartGame);
+
+        if save_slots.latest().is_some() {
+            entries.push(MenuEntry::Load);
+        }
+
+        entries.extend([MenuEntry::Settings, MenuEntry::Attributions, MenuEntry::Quit]);
+
+        entries
+    }
+
+    pub fn load_menu(save_slots: &SaveSlots) -> Vec<Self> {
+        save_slots
+            .saves()
+            .map(|(slot, save)| MenuEntry::LoadSlot {
+                slot,
+                label: format!(
+                    "Slot {} - level {}, {} powers",
+                    slot + 1,
+                    save.player.bonfire_level_index,
+                    save.player.powers.len()
+                ),
+            })
+            .chain([MenuEntry::BackToMain])
+            .collect()
     }
 
     pub fn settings(levels: &UiAudioLevels) -> Vec<Self> {
@@ -62,16 +93,49 @@ impl MenuEntry {
                 tag: 2,
                 name: "Effects level".to_string(),
             },
+            Self::Controls,
             Self::BackToMain
         ]
     }
+
+    pub fn controls(input_map: &InputMap, pending: Option<InputMapAction>) -> Vec<Self> {
+        InputMapAction::ALL
+            .into_iter()
+            .map(|action| {
+                let bindings = if pending == Some(action) {
+                    "press a key or button, <esc> to cancel".to_string()
+                } else {
+                    input_map.describe(action)
+                };
+
+                Self::Binding {
+                    action,
+                    label: format!("{} - {bindings}", action.display_name()),
+                }
+            })
+            .chain([Self::ResetControls, Self::BackToMain])
+            .collect()
+    }
 }
 
 #[derive(Clone)]
 pub enum MenuEntry {
+    Continue,
     StartGame,
+    Load,
+    LoadSlot {
+        slot: u32,
+        label: String,
+    },
     Attributions,
     Settings,
+    Controls,
+    /// Activating it waits for a key or button to bind to the action
+    Binding {
+        action: InputMapAction,
+        label: String,
+    },
+    ResetControls,
     Quit,
     BackToMain,
     AttributionsList(Vec<String>),
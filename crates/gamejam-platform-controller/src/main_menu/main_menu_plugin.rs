@@ -1,11 +1,15 @@
-use crate::main_menu::main_menu_system::{enter_main_menu_system, leave_main_menu_system, main_menu_system, ui_audio_levels_system};
+use crate::input_systems::input_replay::is_replaying_input;
+use crate::main_menu::main_menu_system::{
+    controls_rebind_system, enter_main_menu_system, is_rebinding_controls, leave_main_menu_system,
+    main_menu_system, skip_main_menu_system, ui_audio_levels_system,
+};
 use crate::main_menu::menu_input_system::{
     menu_gamepad_input_system, menu_keyboard_input_system, MenuInput,
 };
 use crate::GameStates;
 use bevy::app::{App, FixedUpdate};
-use bevy::prelude::{in_state, IntoSystemConfigs, OnEnter, OnExit, Plugin, Update};
-use crate::main_menu::main_menu_components::UiAudioLevels;
+use bevy::prelude::{in_state, not, IntoSystemConfigs, OnEnter, OnExit, Plugin, Update};
+use crate::main_menu::main_menu_components::{PendingRebind, UiAudioLevels};
 
 pub struct MainMenuPlugin {}
 
@@ -13,15 +17,19 @@ impl Plugin for MainMenuPlugin {
     fn build(&self, app: &mut App) {
         app.add_event::<MenuInput>()
             .insert_resource(UiAudioLevels::default())
+            .init_resource::<PendingRebind>()
             // input systems must run in Update, since just_pressed* functions
             // get cleared each frame
             .add_systems(
                 Update,
                 (
-                    menu_gamepad_input_system,
-                    menu_keyboard_input_system,
+                    (menu_gamepad_input_system, menu_keyboard_input_system)
+                        .run_if(not(is_rebinding_controls)),
+                    controls_rebind_system,
                 )
-                    .run_if(in_state(GameStates::MainMenu)),
+                    .chain()
+                    .run_if(in_state(GameStates::MainMenu))
+                    .run_if(not(is_replaying_input)),
             )
             .add_systems(
                 FixedUpdate,
@@ -32,6 +40,11 @@ impl Plugin for MainMenuPlugin {
                     .run_if(in_state(GameStates::MainMenu)),
             )
             .add_systems(OnExit(GameStates::MainMenu), leave_main_menu_system)
-            .add_systems(OnEnter(GameStates::MainMenu), enter_main_menu_system);
+            .add_systems(OnEnter(GameStates::MainMenu), enter_main_menu_system)
+            // replays start from the state they recorded, nothing is picked in the menu
+            .add_systems(
+                OnEnter(GameStates::MainMenu),
+                skip_main_menu_system.run_if(is_replaying_input),
+            );
     }
 }
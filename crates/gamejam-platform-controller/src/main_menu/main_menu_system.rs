@@ -1,7 +1,13 @@
 use crate::audio::audio_components::{AudioEffect, AudioLevels, AudioMusic};
 use crate::graphics::sprite_collection::SpriteCollection;
-use crate::main_menu::main_menu_components::{MainMenuComponent, MenuEntry, UiAudioLevels};
+use crate::input_systems::input_map::{InputMap, InputMapAction};
+use crate::main_menu::main_menu_components::{
+    MainMenuComponent, MenuEntry, PendingRebind, UiAudioLevels,
+};
 use crate::main_menu::menu_input_system::MenuInput;
+use crate::save_game::save_file::SaveFile;
+use crate::save_game::{ActiveSaveSlot, LoadedSave, SaveSlots};
+use crate::scripting::scripted_game_entity::{GameData, GameState};
 use crate::GameStates;
 use bevy::color::palettes::tailwind;
 use bevy::ecs::system::SystemState;
@@ -9,7 +15,7 @@ use bevy::prelude::*;
 use bevy::ui::widget::NodeImageMode;
 use haalka::prelude::*;
 use std::time::Duration;
-use bevy_ecs_ldtk::LdtkWorldBundle;
+use bevy_ecs_ldtk::{LdtkWorldBundle, LevelSelection};
 
 pub fn main_menu_system(
     mut commands: Commands,
@@ -19,6 +25,12 @@ pub fn main_menu_system(
     asset_server: Res<AssetServer>,
     mut next_state: ResMut<NextState<GameStates>>,
     mut app_exit_events: ResMut<Events<AppExit>>,
+    save_slots: Res<SaveSlots>,
+    game_data: Res<GameData>,
+    mut level_select: ResMut<LevelSelection>,
+    mut active_slot: ResMut<ActiveSaveSlot>,
+    mut input_map: ResMut<InputMap>,
+    mut pending_rebind: ResMut<PendingRebind>,
     query: Query<&MainMenuComponent>,
 ) {
     let menu = query.single();
@@ -70,16 +82,60 @@ pub fn main_menu_system(
                             PlaybackSettings::ONCE,
                             AudioEffect,
                         ));
-                        
-                        commands.spawn(LdtkWorldBundle {
-                            ldtk_handle: asset_server
-                                .load("maps/grayboxes/nexus_ldtk/nexus.ldtk")
-                                .into(),
-                            ..default()
-                        });
-                        
+
+                        *game_data.game_state.lock().unwrap() = GameState::default();
+                        *level_select = LevelSelection::index(0);
+                        active_slot.0 = save_slots.slot_for_new_game();
+                        commands.remove_resource::<LoadedSave>();
+
+                        start_world(&mut commands, &asset_server);
+                        next_state.set(GameStates::LoadLevel);
+                    }
+                    MenuEntry::Continue => {
+                        let Some((slot, save)) = save_slots.latest() else {
+                            continue;
+                        };
+
+                        commands.spawn((
+                            AudioPlayer::new(start.clone()),
+                            PlaybackSettings::ONCE,
+                            AudioEffect,
+                        ));
+
+                        load_save(&mut commands, &game_data, &mut level_select, save);
+                        active_slot.0 = slot;
+
+                        start_world(&mut commands, &asset_server);
                         next_state.set(GameStates::LoadLevel);
                     }
+                    MenuEntry::LoadSlot { slot, .. } => {
+                        let Some(Some(save)) = save_slots.0.get(slot as usize) else {
+                            continue;
+                        };
+
+                        commands.spawn((
+                            AudioPlayer::new(start.clone()),
+                            PlaybackSettings::ONCE,
+                            AudioEffect,
+                        ));
+
+                        load_save(&mut commands, &game_data, &mut level_select, save);
+                        active_slot.0 = slot;
+
+                        start_world(&mut commands, &asset_server);
+                        next_state.set(GameStates::LoadLevel);
+                    }
+                    MenuEntry::Load => {
+                        commands.spawn((
+                            AudioPlayer::new(click_dark.clone()),
+                            PlaybackSettings::ONCE,
+                            AudioEffect,
+                        ));
+                        menu.entries
+                            .lock_mut()
+                            .replace_cloned(MenuEntry::load_menu(save_slots.as_ref()));
+                        menu.selected_index.set(0);
+                    }
                     MenuEntry::Attributions => {
                         commands.spawn((
                             AudioPlayer::new(click_dark.clone()),
@@ -102,7 +158,7 @@ pub fn main_menu_system(
                         ));
                         menu.entries
                             .lock_mut()
-                            .replace_cloned(MenuEntry::default_menu());
+                            .replace_cloned(MenuEntry::default_menu(save_slots.as_ref()));
                         menu.selected_index.set(0);
                     }
                     MenuEntry::AttributionsList(_) => {}
@@ -117,6 +173,34 @@ pub fn main_menu_system(
                             .replace_cloned(MenuEntry::settings(ui_audio_levels.as_ref()));
                         menu.selected_index.set(0);
                     }
+                    MenuEntry::Controls => {
+                        commands.spawn((
+                            AudioPlayer::new(click_dark.clone()),
+                            PlaybackSettings::ONCE,
+                            AudioEffect,
+                        ));
+                        show_controls(menu, &input_map, None);
+                        menu.selected_index.set(0);
+                    }
+                    MenuEntry::Binding { action, .. } => {
+                        commands.spawn((
+                            AudioPlayer::new(click.clone()),
+                            PlaybackSettings::ONCE,
+                            AudioEffect,
+                        ));
+                        pending_rebind.0 = Some(action);
+                        show_controls(menu, &input_map, pending_rebind.0);
+                    }
+                    MenuEntry::ResetControls => {
+                        commands.spawn((
+                            AudioPlayer::new(click_dark.clone()),
+                            PlaybackSettings::ONCE,
+                            AudioEffect,
+                        ));
+                        *input_map = InputMap::default();
+                        save_input_map(&input_map);
+                        show_controls(menu, &input_map, None);
+                    }
                     _ => {}
                 }
             }
@@ -178,13 +262,98 @@ pub fn main_menu_system(
                 ));
                 menu.entries
                     .lock_mut()
-                    .replace_cloned(MenuEntry::default_menu());
+                    .replace_cloned(MenuEntry::default_menu(save_slots.as_ref()));
                 menu.selected_index.set(0);
             }
         }
     }
 }
 
+/// Takes the next key or button pressed as the binding of the action picked on the controls page
+pub fn controls_rebind_system(
+    mut input_map: ResMut<InputMap>,
+    mut pending_rebind: ResMut<PendingRebind>,
+    key_input: Res<ButtonInput<KeyCode>>,
+    gamepad_query: Query<&Gamepad>,
+    query: Query<&MainMenuComponent>,
+) {
+    let Some(action) = pending_rebind.0 else {
+        return;
+    };
+
+    let Ok(menu) = query.get_single() else {
+        return;
+    };
+
+    if key_input.just_pressed(KeyCode::Escape) {
+        pending_rebind.0 = None;
+        show_controls(menu, &input_map, None);
+        return;
+    }
+
+    if let Some(key) = key_input.get_just_pressed().next() {
+        input_map.bind_key(action, *key);
+    } else if let Some(button) = gamepad_query
+        .iter()
+        .find_map(|gamepad| gamepad.get_just_pressed().next().copied())
+    {
+        input_map.bind_button(action, button);
+    } else {
+        return;
+    }
+
+    pending_rebind.0 = None;
+    save_input_map(&input_map);
+    show_controls(menu, &input_map, None);
+}
+
+pub fn is_rebinding_controls(pending_rebind: Res<PendingRebind>) -> bool {
+    pending_rebind.0.is_some()
+}
+
+fn show_controls(menu: &MainMenuComponent, input_map: &InputMap, pending: Option<InputMapAction>) {
+    menu.entries
+        .lock_mut()
+        .replace_cloned(MenuEntry::controls(input_map, pending));
+}
+
+fn save_input_map(input_map: &InputMap) {
+    if let Err(e) = input_map.save() {
+        error!("failed to save input map: {e:?}");
+    }
+}
+
+fn start_world(commands: &mut Commands, asset_server: &AssetServer) {
+    commands.spawn(LdtkWorldBundle {
+        ldtk_handle: asset_server
+            .load("maps/grayboxes/nexus_ldtk/nexus.ldtk")
+            .into(),
+        ..default()
+    });
+}
+
+/// Replays restore their start state on startup, they go straight into the level
+pub fn skip_main_menu_system(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut next_state: ResMut<NextState<GameStates>>,
+) {
+    start_world(&mut commands, &asset_server);
+    next_state.set(GameStates::LoadLevel);
+}
+
+/// Restores the saved game data, the player part is applied once the player spawns
+fn load_save(
+    commands: &mut Commands,
+    game_data: &GameData,
+    level_select: &mut LevelSelection,
+    save: &SaveFile,
+) {
+    *game_data.game_state.lock().unwrap() = save.game_state.clone();
+    *level_select = LevelSelection::index(save.player.bonfire_level_index as usize);
+    commands.insert_resource(LoadedSave(save.player.clone()));
+}
+
 pub fn leave_main_menu_system(
     mut commands: Commands,
     query: Query<Entity, With<MainMenuComponent>>,
@@ -196,9 +365,9 @@ pub fn leave_main_menu_system(
 
 pub fn enter_main_menu_system(
     world: &mut World,
-    params: &mut SystemState<(Res<SpriteCollection>, Res<AssetServer>)>,
+    params: &mut SystemState<(Res<SpriteCollection>, Res<AssetServer>, Res<SaveSlots>)>,
 ) {
-    let ((sprite_collection, asset_server)) = {
+    let ((sprite_collection, asset_server, save_slots)) = {
         let (a) = params.get(world);
         a
     };
@@ -217,7 +386,7 @@ pub fn enter_main_menu_system(
     let title_image_node =
         El::<ImageNode>::new().image_node(title_image.with_mode(NodeImageMode::Stretch));
 
-    let cmp = MainMenuComponent::default();
+    let cmp = MainMenuComponent::new(&save_slots);
 
     let ui_root = El::<Node>::new()
         .width(Val::Percent(100.))
@@ -287,6 +456,24 @@ pub fn menu_entries(
             };
 
             match entry {
+                MenuEntry::Continue => El::<Text>::new()
+                    .text_font_signal(font_size_enlarge_signal.map(clone!((font) move |factor| {
+                        font.clone().with_font_size(24. * factor)
+                    })))
+                    .text_color_signal(color_signal)
+                    .text(Text::new("Continue")),
+                MenuEntry::Load => El::<Text>::new()
+                    .text_font_signal(font_size_enlarge_signal.map(clone!((font) move |factor| {
+                        font.clone().with_font_size(24. * factor)
+                    })))
+                    .text_color_signal(color_signal)
+                    .text(Text::new("Load Game")),
+                MenuEntry::LoadSlot { label, .. } => El::<Text>::new()
+                    .text_font_signal(font_size_enlarge_signal.map(clone!((font) move |factor| {
+                        font.clone().with_font_size(14. * factor)
+                    })))
+                    .text_color_signal(color_signal)
+                    .text(Text::new(label)),
                 MenuEntry::StartGame => El::<Text>::new()
                     .text_font_signal(font_size_enlarge_signal.map(clone!((font) move |factor| {
                         font.clone().with_font_size(24. * factor)
@@ -327,6 +514,24 @@ pub fn menu_entries(
                             .signal()
                             .map(move |value| Text::new(format!("{name} - [{value}]"))),
                     ),
+                MenuEntry::Controls => El::<Text>::new()
+                    .text_font_signal(font_size_enlarge_signal.map(clone!((font) move |factor| {
+                        font.clone().with_font_size(14. * factor)
+                    })))
+                    .text_color_signal(color_signal)
+                    .text(Text::new("Controls")),
+                MenuEntry::Binding { label, .. } => El::<Text>::new()
+                    .text_font_signal(font_size_enlarge_signal.map(clone!((font) move |factor| {
+                        font.clone().with_font_size(10. * factor)
+                    })))
+                    .text_color_signal(color_signal)
+                    .text(Text::new(label)),
+                MenuEntry::ResetControls => El::<Text>::new()
+                    .text_font_signal(font_size_enlarge_signal.map(clone!((font) move |factor| {
+                        font.clone().with_font_size(10. * factor)
+                    })))
+                    .text_color_signal(color_signal)
+                    .text(Text::new("Reset to defaults")),
                 MenuEntry::Settings => El::<Text>::new()
                     .text_font_signal(font_size_enlarge_signal.map(clone!((font) move |factor| {
                         font.clone().with_font_size(24. * factor)
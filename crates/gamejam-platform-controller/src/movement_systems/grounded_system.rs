@@ -55,11 +55,11 @@ pub fn grounded_system(
             }
 
             jump_state_data.last_grounded_time = Some(now);
-            jump_state_data.jump_start_requested_at = None;
 
             if velocity.y <= 0. {
                 commands.entity(entity).insert(Grounded);
                 jump_state_data.used = 0;
+                jump_state_data.air_dash_used = false;
                 jump_state_data.left_ground_at = None;
             }
         } else {
@@ -2,8 +2,9 @@ use std::time::Duration;
 use crate::player_const_rules::MAX_SPEED;
 use bevy::math::Vec2;
 use bevy::prelude::{Component, Entity, Event, Timer};
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Copy, Component, Debug, Default)]
+#[derive(Clone, Copy, Component, Debug, Default, Serialize, Deserialize)]
 pub enum FacingDirection {
     West,
     #[default]
@@ -33,6 +34,12 @@ pub enum Input {
         strength: f32,
         duration: Duration
     },
+    /// Like a roll, but works in the air and ignores gravity while it lasts
+    Dash {
+        direction: FacingDirection,
+        strength: f32,
+        duration: Duration,
+    },
 }
 
 #[derive(Component)]
@@ -63,6 +70,9 @@ pub struct IgnoreDampening;
 #[derive(Component)]
 pub struct Rolling;
 
+#[derive(Component)]
+pub struct Dashing;
+
 #[derive(Component)]
 pub struct ApplyTimedLinearVelocity {
     pub timer: Timer,
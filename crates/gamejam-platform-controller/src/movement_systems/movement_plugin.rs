@@ -4,6 +4,7 @@ use crate::movement_systems::movement_components::EntityInput;
 use crate::movement_systems::movement_dampening_system::movement_dampening_system;
 use crate::movement_systems::movement_system::{movement_system, rolling_removed_observer};
 use crate::movement_systems::timed_linear_velocity_system::timed_linear_velocity_system;
+use crate::movement_systems::wall_system::wall_system;
 use crate::scripting::scripted_game_entity::scripted_entity_uniform_system;
 use crate::GameStates;
 use bevy::app::{App, FixedUpdate};
@@ -21,6 +22,7 @@ impl Plugin for MovementPlugin {
                         scripted_entity_uniform_system,
                         grounded_system,
                         movement_system,
+                        wall_system,
                         movement_direction_system,
                         timed_linear_velocity_system,
                     ),
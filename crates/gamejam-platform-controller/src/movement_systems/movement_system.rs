@@ -1,6 +1,7 @@
 use crate::combat::combat_components::Invulnerable;
+use crate::combat::status_effects::Stunned;
 use crate::movement_systems::movement_components::{
-    ApplyTimedLinearVelocity, EntityInput, FacingDirection, Input, MovementData,
+    ApplyTimedLinearVelocity, Dashing, EntityInput, FacingDirection, Input, MovementData,
     Rolling,
 };
 use crate::player_const_rules::{SPEED, FALL_GRAVITY};
@@ -10,7 +11,7 @@ use crate::timing::timing_component::{TimerComponent, TimerData};
 use avian2d::prelude::{Collider, GravityScale, LinearVelocity};
 use bevy::math::vec2;
 use bevy::prelude::{
-    Commands, Entity, EventReader, OnRemove, Query, Res, Time, Timer, Trigger,
+    Commands, Entity, EventReader, Has, OnRemove, Query, Res, Time, Timer, Trigger,
     With,
 };
 use bevy::time::TimerMode;
@@ -31,6 +32,7 @@ pub fn movement_system(
         &mut TimerComponent,
         &Collider,
         &GravityScale,
+        Has<Stunned>,
     )>,
 ) {
     let delta_t = time.elapsed_secs();
@@ -47,11 +49,16 @@ pub fn movement_system(
             mut timer,
             _collider,
             gravity,
+            stunned,
         )) = entities.get_mut(event.entity).ok()
         else {
             continue;
         };
 
+        if stunned {
+            continue;
+        }
+
         match event.input {
             Input::Move(delta) => {
                 moving_enemies.insert(entity);
@@ -118,6 +125,55 @@ pub fn movement_system(
                     Collider::rectangle(7., 15.),
                 ));
             }
+            Input::Dash {
+                direction,
+                strength,
+                duration,
+            } => {
+                linear_velocity.x = 0.;
+                linear_velocity.y = 0.;
+
+                let dx = match direction {
+                    FacingDirection::West => -1.,
+                    FacingDirection::East => 1.,
+                };
+
+                *facing_direction = direction;
+
+                let mut entity_commands = commands.entity(entity);
+
+                add_timed_component_to_entity(
+                    &mut entity_commands,
+                    timer.deref_mut(),
+                    Dashing,
+                    duration,
+                );
+
+                let old_gravity = *gravity;
+
+                add_timer_to_entity(
+                    timer.deref_mut(),
+                    TimerData {
+                        timer_name: 1,
+                        timer: Timer::new(duration, TimerMode::Once),
+                        on_expiration: Some(Box::new(move |commands| {
+                            commands.insert(old_gravity);
+                        })),
+                    },
+                );
+
+                entity_commands.insert(GravityScale(0.));
+
+                let duration_secs = duration.as_secs_f32();
+
+                // Front loaded, so the dash bursts out and eases towards its top speed
+                entity_commands.insert(ApplyTimedLinearVelocity {
+                    timer: Timer::new(duration, TimerMode::Once),
+                    acceleration_function: Box::new(move |remaining| {
+                        vec2(dx * strength * remaining / duration_secs, 0.)
+                    }),
+                });
+            }
         }
     }
 
@@ -0,0 +1,55 @@
+use crate::movement_systems::movement_components::FacingDirection;
+use crate::player_const_rules::{WALL_DETECTION_DISTANCE, WALL_SLIDE_SPEED};
+use crate::player_systems::player_components::{
+    AttachedToWall, Grounded, Moving, Player, PowerupWallJump,
+};
+use avian2d::prelude::{LinearVelocity, SpatialQuery, SpatialQueryFilter};
+use bevy::math::Dir2;
+use bevy::prelude::{Commands, Entity, Has, Query, Transform, With};
+
+/// Attaches airborne players holding towards a wall to it, slowing their fall
+pub fn wall_system(
+    mut commands: Commands,
+    mut query: Query<
+        (
+            Entity,
+            &Transform,
+            &FacingDirection,
+            &mut LinearVelocity,
+            Has<Grounded>,
+            Has<Moving>,
+        ),
+        (With<Player>, With<PowerupWallJump>),
+    >,
+    spatial_query: SpatialQuery,
+) {
+    for (entity, transform, facing_direction, mut velocity, grounded, moving) in &mut query {
+        let direction = match facing_direction {
+            FacingDirection::West => Dir2::NEG_X,
+            FacingDirection::East => Dir2::X,
+        };
+
+        let touches_wall = !grounded
+            && moving
+            && spatial_query
+                .cast_ray(
+                    transform.translation.truncate(),
+                    direction,
+                    WALL_DETECTION_DISTANCE,
+                    false,
+                    &SpatialQueryFilter::from_mask(0b00100),
+                )
+                .is_some();
+
+        if !touches_wall {
+            commands.entity(entity).remove::<AttachedToWall>();
+            continue;
+        }
+
+        velocity.y = velocity.y.max(-WALL_SLIDE_SPEED);
+
+        commands.entity(entity).insert(AttachedToWall {
+            wall_direction: *facing_direction,
+        });
+    }
+}
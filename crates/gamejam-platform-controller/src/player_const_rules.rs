@@ -11,3 +11,22 @@ pub const PLAYER_ATTACK_DELAY_SECONDS: f64 = 0.3;
 pub const POGO_HIT_KICKBACK_ACCELERATION: f32 = 400.;
 pub const PLAYER_ATTACK_DURATION: u64 = 200;
 pub const PLAYER_ROLL_DURATION: u64 = 300;
+pub const PLAYER_DASH_DURATION: u64 = 200;
+pub const PLAYER_DASH_STRENGTH: f32 = 6000.;
+// Attacks landing within the parry window are deflected and refund its stamina cost
+pub const PLAYER_PARRY_WINDOW: u64 = 180;
+pub const PLAYER_PARRY_COOLDOWN_SECONDS: f64 = 0.6;
+pub const PLAYER_PARRY_STAMINA_COST: u32 = 20;
+// Invulnerability after taking a hit, and how long the same attacker can't hit again
+pub const PLAYER_HIT_INVULNERABILITY: u64 = 600;
+pub const PLAYER_HIT_ATTACKER_COOLDOWN: u64 = 1000;
+// How long jump, attack and roll presses are held on to when they can't be performed yet
+pub const JUMP_INPUT_BUFFER_SECONDS: f64 = 0.15;
+pub const ATTACK_INPUT_BUFFER_SECONDS: f64 = 0.25;
+pub const ROLL_INPUT_BUFFER_SECONDS: f64 = 0.15;
+pub const DASH_INPUT_BUFFER_SECONDS: f64 = 0.15;
+// Wall slide and wall jump
+pub const WALL_DETECTION_DISTANCE: f32 = 7.;
+pub const WALL_SLIDE_SPEED: f32 = 60.;
+pub const WALL_JUMP_HORIZONTAL_SPEED: f32 = MAX_SPEED;
+pub const WALL_JUMP_INPUT_LOCK: u64 = 150;
@@ -0,0 +1,64 @@
+use crate::movement_systems::movement_components::FacingDirection;
+use crate::player_const_rules::{
+    ATTACK_INPUT_BUFFER_SECONDS, DASH_INPUT_BUFFER_SECONDS, JUMP_INPUT_BUFFER_SECONDS,
+    ROLL_INPUT_BUFFER_SECONDS,
+};
+use crate::AttackDirection;
+use bevy::prelude::*;
+use std::mem::discriminant;
+
+/// Presses that may come in a little before the player is able to act on them
+#[derive(Clone, Copy)]
+pub enum BufferedAction {
+    Jump,
+    Attack(AttackDirection),
+    Roll(FacingDirection),
+    Dash,
+}
+
+impl BufferedAction {
+    pub fn window_seconds(&self) -> f64 {
+        match self {
+            Self::Jump => JUMP_INPUT_BUFFER_SECONDS,
+            Self::Attack(_) => ATTACK_INPUT_BUFFER_SECONDS,
+            Self::Roll(_) => ROLL_INPUT_BUFFER_SECONDS,
+            Self::Dash => DASH_INPUT_BUFFER_SECONDS,
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+struct BufferedInput {
+    action: BufferedAction,
+    buffered_at: f64,
+}
+
+/// Holds on to jump, attack, roll and dash presses until they can be performed, or their window runs out
+#[derive(Component, Default)]
+pub struct InputBuffer {
+    inputs: Vec<BufferedInput>,
+}
+
+impl InputBuffer {
+    /// Remembers a press, replacing an older press of the same action
+    pub fn buffer(&mut self, action: BufferedAction, now: f64) {
+        self.inputs
+            .retain(|input| discriminant(&input.action) != discriminant(&action));
+        self.inputs.push(BufferedInput {
+            action,
+            buffered_at: now,
+        });
+    }
+
+    /// Offers every buffered press still inside its window to `perform`,
+    /// the ones it performs are removed
+    pub fn perform(&mut self, now: f64, mut perform: impl FnMut(BufferedAction) -> bool) {
+        self.inputs.retain(|input| {
+            now - input.buffered_at <= input.action.window_seconds() && !perform(input.action)
+        });
+    }
+
+    pub fn clear(&mut self) {
+        self.inputs.clear();
+    }
+}
@@ -2,5 +2,7 @@ pub mod player_attack_system;
 pub mod player_components;
 pub mod player_control_system;
 pub mod player_health;
+pub mod player_powers;
 pub mod player_spawn_system;
 pub mod bonfire;
+pub mod input_buffer;
@@ -1,14 +1,12 @@
 use crate::audio::audio_components::AudioEffect;
-use crate::combat::attackable::{Attackable, Attacked};
-use crate::graphics::animation_system::SpriteAnimation;
+use crate::combat::hitboxes::HitLanded;
 use crate::graphics::sprite_collection::SpriteCollection;
 use crate::player_const_rules::{PLAYER_ATTACK_DURATION, POGO_HIT_KICKBACK_ACCELERATION};
 use crate::player_systems::player_components::{
-    Attacking, JumpState, Player, PlayerMovementData, Pogoing, PowerupPogo, PowerupRoll,
+    Attacking, JumpState, Player, PlayerMovementData, Pogoing, PowerupPogo,
 };
 use crate::AttackDirection;
-use avian2d::position::Position;
-use avian2d::prelude::{Collider, LinearVelocity, SpatialQuery, SpatialQueryFilter};
+use avian2d::prelude::{LinearVelocity, SpatialQuery, SpatialQueryFilter};
 use bevy::prelude::*;
 use bevy_prng::ChaCha8Rng;
 use bevy_rand::prelude::GlobalEntropy;
@@ -16,55 +14,21 @@ use bevy_trauma_shake::Shake;
 use rand_core::RngCore;
 use std::time::Duration;
 
+/// Starts the attack animation, its hitboxes in the sprite file do the hitting
 pub fn player_attack_start_system(
     mut commands: Commands,
     mut rng: GlobalEntropy<ChaCha8Rng>,
     asset_server: Res<AssetServer>,
-    time: Res<Time>,
     sprite_collection: Res<SpriteCollection>,
-    mut player: Query<
-        (
-            Entity,
-            &Attacking,
-            &mut LinearVelocity,
-            &mut SpriteAnimation,
-            &mut JumpState,
-            &Transform,
-            &mut PlayerMovementData,
-            Option<&PowerupPogo>,
-        ),
+    player: Query<
+        (Entity, &Attacking, &PlayerMovementData),
         (With<Player>, Added<Attacking>),
     >,
-    mut attackables: Query<
-        (Entity, &Transform, &Collider, Option<&mut LinearVelocity>),
-        (With<Attackable>, Without<Player>),
-    >,
-    mut camera_shake: Query<&mut Shake, With<Camera2d>>,
 ) {
-    let Ok((
-        entity,
-        attacking,
-        mut velocity,
-        animation,
-        mut jump_state,
-        player_transform,
-        movement_data,
-        powerup_pogo,
-    )) = player.get_single_mut()
-    else {
-        return;
-    };
-
-    let Ok(mut camera_shake) = camera_shake.get_single_mut() else {
+    let Ok((entity, attacking, movement_data)) = player.get_single() else {
         return;
     };
 
-    let attack_direction_unit = if movement_data.horizontal_direction {
-        -1.
-    } else {
-        1.
-    };
-
     let is_pogo = attacking.direction == AttackDirection::Down;
 
     commands.entity(entity).insert(
@@ -108,60 +72,51 @@ pub fn player_attack_start_system(
         }
     };
 
-    let mut did_attack_trauma = false;
-
-    // Process hits
-    for (attacked_entity, attacked_transform, attacked_collider, _attacked_linear_velocity) in
-        attackables.iter_mut()
-    {
-        let attack_ray_direction = if is_pogo {
-            Vec2::new(0., -1.)
-        } else {
-            Vec2::new(attack_direction_unit, 0.)
-        };
-
-        let hit = attacked_collider.intersects_ray(
-            Position::from_xy(
-                attacked_transform.translation.x,
-                attacked_transform.translation.y,
-            ),
-            0.,
-            player_transform.translation.truncate(),
-            attack_ray_direction,
-            if is_pogo { 64. } else { 32. },
-        );
+    if is_pogo {
+        commands.entity(entity).insert(Pogoing);
+    }
+}
 
-        if !hit {
-            continue;
-        }
+/// Shakes the camera when an attack of the player connects, and bounces off whatever a pogo hit
+pub fn player_hit_landed_observer(
+    trigger: Trigger<HitLanded>,
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    time: Res<Time>,
+    mut player: Query<
+        (
+            &mut LinearVelocity,
+            &mut JumpState,
+            Option<&PowerupPogo>,
+            Has<Pogoing>,
+        ),
+        With<Player>,
+    >,
+    mut camera_shake: Query<&mut Shake, With<Camera2d>>,
+) {
+    let entity = trigger.entity();
 
-        if !did_attack_trauma {
-            camera_shake.add_trauma(0.1);
-            did_attack_trauma = true;
-        }
+    let Ok((mut velocity, mut jump_state, powerup_pogo, pogoing)) = player.get_mut(entity) else {
+        return;
+    };
 
-        commands.entity(attacked_entity).insert(Attacked {
-            damage: 5,
-            vector: attack_ray_direction,
-            origin: player_transform.translation.truncate(),
-            force: 2.,
-        });
-        if is_pogo {
-            apply_pogo(
-                &mut commands,
-                asset_server.as_ref(),
-                powerup_pogo,
-                &time,
-                &mut camera_shake,
-                entity,
-                &mut velocity,
-                &mut jump_state,
-            );
-        }
-    }
+    let Ok(mut camera_shake) = camera_shake.get_single_mut() else {
+        return;
+    };
 
-    if is_pogo {
-        commands.entity(entity).insert(Pogoing);
+    camera_shake.add_trauma(0.1);
+
+    if pogoing {
+        apply_pogo(
+            &mut commands,
+            asset_server.as_ref(),
+            powerup_pogo,
+            &time,
+            &mut camera_shake,
+            entity,
+            &mut velocity,
+            &mut jump_state,
+        );
     }
 }
 
@@ -1,6 +1,8 @@
 use crate::player_systems::bonfire::Bonfire;
+use crate::player_systems::input_buffer::InputBuffer;
 use crate::timing::timing_component::TimerComponent;
 use crate::combat::combat_components::Health;
+use crate::combat::combat_components::HitInvulnerability;
 use crate::combat::combat_components::Stamina;
 use crate::combat::attackable::Attackable;
 use crate::movement_systems::movement_components::FacingDirection;
@@ -28,6 +30,7 @@ use simple_2d_camera::PixelCameraTracked;
     PixelCameraTracked,
     Friction(|| Friction::new(0.)),
     PlayerActionTracker,
+    InputBuffer,
     PlayerMovementData,
     PlayerStats,
     Health(|| Health::default_player()),
@@ -35,6 +38,7 @@ use simple_2d_camera::PixelCameraTracked;
     MovementData(|| MovementData::default_player()),
     Attackable,
     Stamina(|| Stamina::default_player()),
+    HitInvulnerability(|| HitInvulnerability::default_player()),
     TimerComponent,
     Bonfire
 )]
@@ -43,8 +47,15 @@ pub struct Player;
 #[derive(Component, Default)]
 pub struct Grounded;
 
-#[derive(Component, Default)]
-pub struct AttachedToWall;
+/// Sliding down a wall while holding towards it
+#[derive(Component)]
+pub struct AttachedToWall {
+    pub wall_direction: FacingDirection,
+}
+
+/// Pushed away from a wall, horizontal input is ignored until this is removed
+#[derive(Component)]
+pub struct WallJumping;
 
 #[derive(Component, Default)]
 pub struct Moving;
@@ -55,6 +66,12 @@ pub struct PowerupRoll;
 #[derive(Component, Default)]
 pub struct PowerupPogo;
 
+#[derive(Component, Default)]
+pub struct PowerupWallJump;
+
+#[derive(Component, Default)]
+pub struct PowerupDash;
+
 #[derive(Copy, Clone, Eq, PartialEq)]
 pub enum Direction {
     Left,
@@ -66,6 +83,13 @@ pub enum Direction {
 #[derive(Component)]
 pub struct Pogoing;
 
+/// Attacks landing while this is present are deflected
+#[derive(Component, Default)]
+pub struct Parrying {
+    /// The parry's stamina is given back on the first deflect only
+    pub stamina_refunded: bool,
+}
+
 #[derive(Component)]
 pub struct Attacking {
     pub attack_started_at: f64,
@@ -83,6 +107,7 @@ pub struct AttackSprite;
 #[derive(Component, Default)]
 pub struct PlayerActionTracker {
     pub last_attack_at: Option<f64>,
+    pub last_parry_at: Option<f64>,
 }
 
 #[derive(Component, Default)]
@@ -90,7 +115,8 @@ pub struct JumpState {
     pub used: u8,
     pub left_ground_at: Option<f64>,
     pub last_grounded_time: Option<f64>,
-    pub jump_start_requested_at: Option<f32>,
+    /// The air dash can only be used once until the player lands again
+    pub air_dash_used: bool,
 }
 
 impl JumpState {
@@ -121,6 +147,7 @@ pub struct PlayerStatsMutable {
     pub stamina: StatBarMutables,
     pub has_pogo: Mutable<bool>,
     pub has_rolling: Mutable<bool>,
+    pub has_dash: Mutable<bool>,
 }
 
 #[derive(Default, Clone)]
@@ -1,17 +1,26 @@
 use crate::combat::combat_components::Stamina;
+use crate::combat::status_effects::Stunned;
 use crate::graphics::animation_system::SpriteAnimation;
 use crate::graphics::sprite_collection::SpriteCollection;
 use crate::input_systems::PlayerInputAction;
 use crate::ldtk_entities::interactable::{InteractableInRange, Interacted};
-use crate::movement_systems::movement_components::{EntityInput, FacingDirection, Input, Rolling};
+use crate::movement_systems::movement_components::{
+    Dashing, EntityInput, FacingDirection, IgnoreDampening, Input, Rolling,
+};
 use crate::player_const_rules::{
     JUMP_SPEED, MAX_JUMP_ACCELERATION_TIME, MAX_Y_SPEED, PLAYER_ATTACK_DELAY_SECONDS,
-    PLAYER_ROLL_DURATION,
+    PLAYER_DASH_DURATION, PLAYER_DASH_STRENGTH, PLAYER_PARRY_COOLDOWN_SECONDS,
+    PLAYER_PARRY_STAMINA_COST, PLAYER_PARRY_WINDOW, PLAYER_ROLL_DURATION,
+    WALL_JUMP_HORIZONTAL_SPEED, WALL_JUMP_INPUT_LOCK,
 };
+use crate::player_systems::input_buffer::{BufferedAction, InputBuffer};
 use crate::player_systems::player_components::{
-    Attacking, Grounded, JumpState, Moving, Player, PlayerActionTracker, PlayerMovementData,
-    Pogoing, PowerupPogo, PowerupRoll,
+    AttachedToWall, Attacking, Grounded, JumpState, Moving, Parrying, Player,
+    PlayerActionTracker, PlayerMovementData, Pogoing, PowerupDash, PowerupPogo, PowerupRoll,
+    PowerupWallJump, WallJumping,
 };
+use crate::timing::timer_system::add_timed_component_to_entity;
+use crate::timing::timing_component::TimerComponent;
 use avian2d::prelude::*;
 use bevy::prelude::*;
 use bevy_ecs_ldtk::{LevelIid, LevelSelection, Respawn};
@@ -39,6 +48,15 @@ pub fn player_control_system(
             Option<&Rolling>,
             &FacingDirection,
             Option<&PowerupRoll>,
+            &mut InputBuffer,
+            (
+                Option<&AttachedToWall>,
+                Has<WallJumping>,
+                Has<Dashing>,
+                Has<PowerupDash>,
+                Has<Stunned>,
+                &mut TimerComponent,
+            ),
         ),
         With<Player>,
     >,
@@ -58,10 +76,30 @@ pub fn player_control_system(
         rolling,
         facing_direction,
         powerup_roll,
+        mut input_buffer,
+        (attached_to_wall, wall_jumping, dashing, powerup_dash, stunned, mut timer),
     ) in player_velocity.iter_mut()
     {
         linear_velocity.y = linear_velocity.y.clamp(-MAX_Y_SPEED, MAX_Y_SPEED);
 
+        let now = time.elapsed_secs_f64();
+        let input_actions: Vec<PlayerInputAction> = player_input_reader.read().cloned().collect();
+
+        // Buffered even mid-swing or mid-roll, so they fire as soon as the player is free again
+        for input_action in input_actions.iter() {
+            match *input_action {
+                PlayerInputAction::JumpStart => input_buffer.buffer(BufferedAction::Jump, now),
+                PlayerInputAction::Attack(direction) => {
+                    input_buffer.buffer(BufferedAction::Attack(direction), now)
+                }
+                PlayerInputAction::Roll(direction) => {
+                    input_buffer.buffer(BufferedAction::Roll(direction), now)
+                }
+                PlayerInputAction::Dash => input_buffer.buffer(BufferedAction::Dash, now),
+                _ => {}
+            }
+        }
+
         if let Some(_attacking) = attacking {
             if animation.finished() {
                 commands.entity(entity).insert(
@@ -83,13 +121,13 @@ pub fn player_control_system(
             continue;
         }
 
-        if rolling.is_some() {
+        if rolling.is_some() || dashing || stunned {
             continue;
         }
 
         let mut still_moving = false;
 
-        for input_action in player_input_reader.read() {
+        for input_action in input_actions.iter() {
             match input_action {
                 PlayerInputAction::Horizontal(dir) => {
                     still_moving = true;
@@ -111,7 +149,7 @@ pub fn player_control_system(
                         }
                     }
 
-                    if attacking.is_some() {
+                    if attacking.is_some() || wall_jumping {
                         continue;
                     }
 
@@ -123,40 +161,35 @@ pub fn player_control_system(
                     movement_data.horizontal_direction = dir.x < 0.;
                 }
                 PlayerInputAction::Jump => {
-                    do_jump(&time, &mut linear_velocity, grounded, &mut jump_state);
-                }
-                PlayerInputAction::JumpStart => {
-                    if jump_state.jump_start_requested_at.is_none() {
-                        jump_state.jump_start_requested_at = Some(time.elapsed_secs());
-                    }
-
-                    do_jump(&time, &mut linear_velocity, grounded, &mut jump_state);
+                    sustain_jump(now, &mut linear_velocity, &jump_state);
                 }
+                // Performed through the input buffer below
+                PlayerInputAction::JumpStart
+                | PlayerInputAction::Attack(_)
+                | PlayerInputAction::Roll(_)
+                | PlayerInputAction::Dash => {}
                 PlayerInputAction::JumpAbort => {
                     if linear_velocity.y > 0.5 {
                         linear_velocity.y = 0.;
                         jump_state.abort_jump();
                     }
                 }
-                PlayerInputAction::Attack(direction) => {
-                    let now = time.elapsed_secs_f64();
-
-                    if now - player_actions.last_attack_at.unwrap_or(0.)
-                        < PLAYER_ATTACK_DELAY_SECONDS
+                PlayerInputAction::Parry => {
+                    if now - player_actions.last_parry_at.unwrap_or(0.)
+                        < PLAYER_PARRY_COOLDOWN_SECONDS
+                        || !stamina.0.try_consume(PLAYER_PARRY_STAMINA_COST)
                     {
-                        return;
-                    }
-
-                    if !stamina.0.try_consume(25) {
                         continue;
                     }
 
-                    player_actions.last_attack_at = Some(now);
+                    player_actions.last_parry_at = Some(now);
 
-                    commands.entity(entity).insert(Attacking {
-                        attack_started_at: now,
-                        direction: *direction,
-                    });
+                    add_timed_component_to_entity(
+                        &mut commands.entity(entity),
+                        &mut timer,
+                        Parrying::default(),
+                        Duration::from_millis(PLAYER_PARRY_WINDOW),
+                    );
                 }
                 PlayerInputAction::Interact => {
                     if let Ok(interactable_entity) = interactables.get_single() {
@@ -167,43 +200,12 @@ pub fn player_control_system(
                     let (level, _) = level.single();
                     commands.entity(level).insert(Respawn);
                 }
-                &PlayerInputAction::Roll(direction) => {
-                    if powerup_roll.is_none() {
-                        continue;
-                    }
-
-                    if grounded.is_none() || !stamina.0.try_consume(25) {
-                        continue;
-                    }
-
-                    commands.entity(entity).insert(
-                        sprites
-                            .create_sprite_animation_bundle(
-                                "player",
-                                "roll",
-                                Duration::from_millis(PLAYER_ROLL_DURATION),
-                                false,
-                                false,
-                                false,
-                            )
-                            .unwrap(),
-                    );
-
-                    movement_event_writer.send(EntityInput {
-                        entity,
-                        input: Input::Roll {
-                            direction,
-                            strength: 2500.,
-                            duration: Duration::from_millis(PLAYER_ROLL_DURATION),
-                        },
-                    });
-
-                    return;
-                }
                 &PlayerInputAction::GoToBoss => {
                     commands.entity(entity).insert((
                         PowerupRoll,
                         PowerupPogo,
+                        PowerupWallJump,
+                        PowerupDash,
                         RequestedPlayerSpawn {
                             spawn_name: "entry".to_string(),
                         },
@@ -213,6 +215,119 @@ pub fn player_control_system(
             }
         }
 
+        let mut started_roll_or_dash = false;
+
+        input_buffer.perform(now, |action| match action {
+            BufferedAction::Jump => {
+                if let Some(attached_to_wall) = attached_to_wall {
+                    wall_jump(
+                        now,
+                        &mut linear_velocity,
+                        &mut jump_state,
+                        attached_to_wall.wall_direction,
+                    );
+
+                    add_timed_component_to_entity(
+                        &mut commands.entity(entity),
+                        &mut timer,
+                        (WallJumping, IgnoreDampening),
+                        Duration::from_millis(WALL_JUMP_INPUT_LOCK),
+                    );
+
+                    return true;
+                }
+
+                try_start_jump(now, &mut linear_velocity, grounded, &mut jump_state)
+            }
+            BufferedAction::Attack(direction) => {
+                if now - player_actions.last_attack_at.unwrap_or(0.) < PLAYER_ATTACK_DELAY_SECONDS
+                    || !stamina.0.try_consume(25)
+                {
+                    return false;
+                }
+
+                player_actions.last_attack_at = Some(now);
+
+                commands.entity(entity).insert(Attacking {
+                    attack_started_at: now,
+                    direction,
+                });
+
+                true
+            }
+            BufferedAction::Roll(direction) => {
+                if powerup_roll.is_none() || grounded.is_none() || !stamina.0.try_consume(25) {
+                    return false;
+                }
+
+                commands.entity(entity).insert(
+                    sprites
+                        .create_sprite_animation_bundle(
+                            "player",
+                            "roll",
+                            Duration::from_millis(PLAYER_ROLL_DURATION),
+                            false,
+                            false,
+                            false,
+                        )
+                        .unwrap(),
+                );
+
+                movement_event_writer.send(EntityInput {
+                    entity,
+                    input: Input::Roll {
+                        direction,
+                        strength: 2500.,
+                        duration: Duration::from_millis(PLAYER_ROLL_DURATION),
+                    },
+                });
+
+                started_roll_or_dash = true;
+                true
+            }
+            BufferedAction::Dash => {
+                let in_air = grounded.is_none();
+
+                if !powerup_dash
+                    || (in_air && jump_state.air_dash_used)
+                    || !stamina.0.try_consume(25)
+                {
+                    return false;
+                }
+
+                jump_state.air_dash_used |= in_air;
+
+                commands.entity(entity).insert(
+                    sprites
+                        .create_sprite_animation_bundle(
+                            "player",
+                            "roll",
+                            Duration::from_millis(PLAYER_DASH_DURATION),
+                            false,
+                            false,
+                            facing_direction.to_bool(),
+                        )
+                        .unwrap(),
+                );
+
+                movement_event_writer.send(EntityInput {
+                    entity,
+                    input: Input::Dash {
+                        direction: *facing_direction,
+                        strength: PLAYER_DASH_STRENGTH,
+                        duration: Duration::from_millis(PLAYER_DASH_DURATION),
+                    },
+                });
+
+                started_roll_or_dash = true;
+                true
+            }
+        });
+
+        if started_roll_or_dash {
+            continue;
+        }
+
         if jump_state.left_ground_at.is_some()
             && attacking.is_none()
             && animation.animation_name != "fall"
@@ -252,32 +367,50 @@ pub fn player_control_system(
     }
 }
 
-fn do_jump(
-    time: &Res<Time>,
+/// Starts a jump when standing on the ground, or shortly after walking off a ledge
+fn try_start_jump(
+    now: f64,
     linear_velocity: &mut Mut<LinearVelocity>,
     grounded: Option<&Grounded>,
     jump_state: &mut Mut<JumpState>,
-) {
-    let now = time.elapsed_secs_f64();
-    let left_ground_at = jump_state.left_ground_at;
-    let is_grounded = grounded.is_some();
-
+) -> bool {
     let coyote_time_delta = now - jump_state.last_grounded_time.unwrap_or(0.);
     let can_coyote_jump = coyote_time_delta <= 0.1;
 
-    let start = match jump_state.jump_start_requested_at {
-        Some(time_requested) => time.elapsed_secs() - time_requested < 0.3,
-        _ => false,
+    if grounded.is_none() && !(can_coyote_jump && jump_state.used == 0) {
+        return false;
+    }
+
+    jump_state.used = 2;
+    jump_state.left_ground_at = Some(now);
+    linear_velocity.y = JUMP_SPEED;
+
+    true
+}
+
+/// Jumps off a wall, pushing the player away from it
+fn wall_jump(
+    now: f64,
+    linear_velocity: &mut Mut<LinearVelocity>,
+    jump_state: &mut Mut<JumpState>,
+    wall_direction: FacingDirection,
+) {
+    let away_from_wall = match wall_direction {
+        FacingDirection::West => 1.,
+        FacingDirection::East => -1.,
     };
 
-    if start && (is_grounded || can_coyote_jump && jump_state.used == 0) {
-        jump_state.used = 1;
-        jump_state.left_ground_at = Some(now);
-        linear_velocity.y = JUMP_SPEED;
-        jump_state.used += 1;
-        jump_state.jump_start_requested_at = None;
-    } else if left_ground_at.is_some() && now - left_ground_at.unwrap() < MAX_JUMP_ACCELERATION_TIME
-    {
-        linear_velocity.y = JUMP_SPEED;
+    jump_state.used = 2;
+    jump_state.left_ground_at = Some(now);
+    linear_velocity.x = away_from_wall * WALL_JUMP_HORIZONTAL_SPEED;
+    linear_velocity.y = JUMP_SPEED;
+}
+
+/// Keeps accelerating upwards while jump is held, up to `MAX_JUMP_ACCELERATION_TIME`
+fn sustain_jump(now: f64, linear_velocity: &mut Mut<LinearVelocity>, jump_state: &JumpState) {
+    if let Some(left_ground_at) = jump_state.left_ground_at {
+        if now - left_ground_at < MAX_JUMP_ACCELERATION_TIME {
+            linear_velocity.y = JUMP_SPEED;
+        }
     }
 }
@@ -1,19 +1,20 @@
-use crate::player_systems::player_components::{Player, PlayerStatsMutable, PowerupPogo, PowerupRoll};
+use crate::player_systems::player_components::{Player, PlayerStatsMutable, PowerupDash, PowerupPogo, PowerupRoll};
 use bevy::prelude::{Commands, Entity, NextState, Query, ResMut, With, Without};
 use bevy_ecs_ldtk::LevelSelection;
 use crate::combat::combat_components::{Health, Stamina};
 use crate::GameStates;
 use crate::ldtk_entities::player_spawn::RequestedPlayerSpawn;
 use crate::player_systems::bonfire::Bonfire;
+use crate::player_systems::input_buffer::InputBuffer;
 
 pub fn player_health_sync_system(
     mut commands: Commands,
     mut level_select: ResMut<LevelSelection>,
     mut next_state: ResMut<NextState<GameStates>>,
     player_stats: Query<&PlayerStatsMutable, Without<Player>>,
-    mut player_hp: Query<(Entity, &Stamina, &mut Health, Option<&PowerupPogo>, Option<&PowerupRoll>, &Bonfire), With<Player>>,
+    mut player_hp: Query<(Entity, &Stamina, &mut Health, Option<&PowerupPogo>, Option<&PowerupRoll>, Option<&PowerupDash>, &Bonfire, &mut InputBuffer), With<Player>>,
 ) {
-    let Ok((player, stamina, mut health, pogo, roll, bonfire)) = player_hp.get_single_mut() else {
+    let Ok((player, stamina, mut health, pogo, roll, dash, bonfire, mut input_buffer)) = player_hp.get_single_mut() else {
         return;
     };
 
@@ -23,6 +24,7 @@ pub fn player_health_sync_system(
 
     if health.0.current == 0 && health.0.newly_consumed == 0 {
         health.0.current = health.0.max;
+        input_buffer.clear();
         commands.entity(player).insert(RequestedPlayerSpawn { spawn_name: bonfire.spawn_name.clone() });
         *level_select = LevelSelection::index(bonfire.level_index as usize);
         
@@ -37,4 +39,5 @@ pub fn player_health_sync_system(
     stats.stamina.newly_consumed.set(stamina.0.newly_consumed);
     stats.has_pogo.set(pogo.is_some());
     stats.has_rolling.set(roll.is_some());
+    stats.has_dash.set(dash.is_some());
 }
@@ -0,0 +1,48 @@
+use crate::player_systems::player_components::{PowerupDash, PowerupPogo, PowerupRoll, PowerupWallJump};
+use bevy::ecs::query::QueryData;
+use bevy::prelude::*;
+
+/// Powers the player currently has, by the names scripts grant them with
+#[derive(QueryData)]
+pub struct PlayerPowers {
+    roll: Has<PowerupRoll>,
+    pogo: Has<PowerupPogo>,
+    wall_jump: Has<PowerupWallJump>,
+    dash: Has<PowerupDash>,
+}
+
+impl PlayerPowersItem<'_> {
+    pub fn names(&self) -> Vec<String> {
+        [
+            ("roll", self.roll),
+            ("pogo", self.pogo),
+            ("wall-jump", self.wall_jump),
+            ("dash", self.dash),
+        ]
+        .into_iter()
+        .filter(|(_, has_power)| *has_power)
+        .map(|(name, _)| name.to_string())
+        .collect()
+    }
+}
+
+/// Inserts the component backing a power, returns false for unknown powers
+pub fn grant_player_power(player: &mut EntityCommands, power: &str) -> bool {
+    match power {
+        "roll" => {
+            player.insert(PowerupRoll);
+        }
+        "pogo" => {
+            player.insert(PowerupPogo);
+        }
+        "wall-jump" => {
+            player.insert(PowerupWallJump);
+        }
+        "dash" => {
+            player.insert(PowerupDash);
+        }
+        _ => return false,
+    }
+
+    true
+}
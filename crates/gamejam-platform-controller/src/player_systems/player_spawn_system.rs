@@ -1,6 +1,10 @@
+use crate::combat::combat_components::Health;
 use crate::graphics::animation_system::{spawn_animated_sprite_for_entity, SpriteSettings};
 use crate::ldtk_entities::player_spawn::RequestedPlayerSpawn;
+use crate::player_systems::bonfire::Bonfire;
 use crate::player_systems::player_components::{Player, PlayerStatsMutable};
+use crate::player_systems::player_powers::grant_player_power;
+use crate::save_game::LoadedSave;
 use crate::{GameStates, PlayerAssets};
 use bevy::prelude::{Commands, NextState, Res, ResMut};
 use bevy::utils::default;
@@ -10,6 +14,7 @@ pub fn spawn_player_system(
     mut commands: Commands,
     player_assets: Res<PlayerAssets>,
     mut next_state: ResMut<NextState<GameStates>>,
+    loaded_save: Option<Res<LoadedSave>>,
 ) {
     let mut entity = commands.spawn((
         Player,
@@ -31,6 +36,31 @@ pub fn spawn_player_system(
         },
     );
 
+    if let Some(loaded_save) = loaded_save {
+        let saved = &loaded_save.0;
+
+        let mut health = Health::default_player();
+        health.0.max = saved.max_health;
+        health.0.current = saved.health.min(saved.max_health);
+
+        entity.insert((
+            RequestedPlayerSpawn {
+                spawn_name: saved.bonfire_spawn_name.clone(),
+            },
+            Bonfire {
+                level_index: saved.bonfire_level_index,
+                spawn_name: saved.bonfire_spawn_name.clone(),
+            },
+            health,
+        ));
+
+        for power in &saved.powers {
+            grant_player_power(&mut entity, power);
+        }
+
+        commands.remove_resource::<LoadedSave>();
+    }
+
     next_state.set(GameStates::GameLoop);
 }
 
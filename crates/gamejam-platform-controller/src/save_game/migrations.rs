@@ -0,0 +1,174 @@
+use anyhow::{anyhow, bail};
+use serde_json::Value;
+
+/// Upgrades a save from one version to the next, working on the raw json
+/// so the old layout doesn't need its own types
+pub type SaveMigration = fn(&mut Value) -> anyhow::Result<()>;
+
+/// Migration at index `n` upgrades a save from version `n + 1` to `n + 2`.
+/// Changing the save layout, or renaming a `state-variable` key scripts use,
+/// means appending a migration here, which also bumps the save version.
+pub const SAVE_MIGRATIONS: &[SaveMigration] = &[];
+
+pub const fn current_save_version() -> u32 {
+    SAVE_MIGRATIONS.len() as u32 + 1
+}
+
+pub fn save_version(save: &Value) -> anyhow::Result<u32> {
+    save.get("version")
+        .and_then(Value::as_u64)
+        .map(|version| version as u32)
+        .ok_or_else(|| anyhow!("save has no version"))
+}
+
+/// Runs every migration between the version of the save and the current one,
+/// returns the version the save started at
+pub fn migrate_save(save: &mut Value) -> anyhow::Result<u32> {
+    run_migrations(save, SAVE_MIGRATIONS)
+}
+
+fn run_migrations(save: &mut Value, migrations: &[SaveMigration]) -> anyhow::Result<u32> {
+    let original_version = save_version(save)?;
+    let current_version = migrations.len() as u32 + 1;
+
+    if original_version == 0 || original_version > current_version {
+        bail!("save has version {original_version}, this build supports 1 to {current_version}");
+    }
+
+    for version in original_version..current_version {
+        migrations[version as usize - 1](save)
+            .map_err(|e| e.context(format!("migrating save from version {version}")))?;
+
+        save["version"] = Value::from(version + 1);
+    }
+
+    Ok(original_version)
+}
+
+/// Renames a global `state-variable` key, for migrations following a script change
+pub fn rename_game_state_key(save: &mut Value, from: &str, to: &str) -> anyhow::Result<()> {
+    for map in ["strings", "ints"] {
+        let values = save
+            .pointer_mut(&format!("/game_state/{map}"))
+            .and_then(Value::as_object_mut)
+            .ok_or_else(|| anyhow!("save has no game_state.{map}"))?;
+
+        if let Some(value) = values.remove(from) {
+            values.insert(to.to_string(), value);
+        }
+    }
+
+    Ok(())
+}
+
+/// Renames a key in the self-state of every entity, for migrations following a script change
+pub fn rename_entity_state_key(save: &mut Value, from: &str, to: &str) -> anyhow::Result<()> {
+    let entities = save
+        .pointer_mut("/game_state/entity_states")
+        .and_then(Value::as_object_mut)
+        .ok_or_else(|| anyhow!("save has no game_state.entity_states"))?;
+
+    for state in entities.values_mut().filter_map(Value::as_object_mut) {
+        if let Some(value) = state.remove(from) {
+            state.insert(to.to_string(), value);
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const TEST_MIGRATIONS: &[SaveMigration] = &[
+        |save| rename_game_state_key(save, "door_open", "vault_door_open"),
+        |save| {
+            save["game_state"]["ints"]["migrated"] = Value::from(1);
+            Ok(())
+        },
+    ];
+
+    fn save(version: u32) -> Value {
+        json!({
+            "version": version,
+            "game_state": {
+                "strings": { "door_open": "yes", "name": "hero" },
+                "ints": { "door_open": 1 },
+                "entity_states": {
+                    "iid-1": { "state": { "Int": 1 } },
+                    "iid-2": { "other": { "Bool": true } },
+                },
+            },
+        })
+    }
+
+    #[test]
+    fn runs_every_migration_after_the_save_version() {
+        let mut migrated = save(1);
+
+        assert_eq!(run_migrations(&mut migrated, TEST_MIGRATIONS).unwrap(), 1);
+        assert_eq!(migrated["version"], 3);
+        assert_eq!(migrated["game_state"]["ints"]["vault_door_open"], 1);
+        assert_eq!(migrated["game_state"]["ints"]["migrated"], 1);
+    }
+
+    #[test]
+    fn skips_migrations_the_save_already_went_through() {
+        let mut migrated = save(2);
+
+        assert_eq!(run_migrations(&mut migrated, TEST_MIGRATIONS).unwrap(), 2);
+        assert_eq!(migrated["version"], 3);
+        assert_eq!(migrated["game_state"]["ints"]["door_open"], 1);
+        assert_eq!(migrated["game_state"]["ints"]["migrated"], 1);
+    }
+
+    #[test]
+    fn rejects_saves_from_future_builds_and_without_version() {
+        assert!(run_migrations(&mut save(4), TEST_MIGRATIONS).is_err());
+        assert!(run_migrations(&mut save(0), TEST_MIGRATIONS).is_err());
+        assert!(run_migrations(&mut json!({}), TEST_MIGRATIONS).is_err());
+    }
+
+    #[test]
+    fn a_current_save_is_left_alone() {
+        let mut current = save(current_save_version());
+
+        assert_eq!(migrate_save(&mut current).unwrap(), current_save_version());
+        assert_eq!(current, save(current_save_version()));
+    }
+
+    #[test]
+    fn renames_game_state_keys_in_strings_and_ints() {
+        let mut renamed = save(1);
+
+        rename_game_state_key(&mut renamed, "door_open", "vault_door_open").unwrap();
+
+        assert_eq!(
+            renamed["game_state"]["strings"],
+            json!({ "vault_door_open": "yes", "name": "hero" })
+        );
+        assert_eq!(
+            renamed["game_state"]["ints"],
+            json!({ "vault_door_open": 1 })
+        );
+        assert!(rename_game_state_key(&mut json!({}), "a", "b").is_err());
+    }
+
+    #[test]
+    fn renames_entity_state_keys_of_every_entity() {
+        let mut renamed = save(1);
+
+        rename_entity_state_key(&mut renamed, "state", "door_state").unwrap();
+
+        assert_eq!(
+            renamed["game_state"]["entity_states"],
+            json!({
+                "iid-1": { "door_state": { "Int": 1 } },
+                "iid-2": { "other": { "Bool": true } },
+            })
+        );
+        assert!(rename_entity_state_key(&mut json!({}), "a", "b").is_err());
+    }
+}
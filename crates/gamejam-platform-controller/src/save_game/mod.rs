@@ -0,0 +1,119 @@
+use crate::combat::combat_components::Health;
+use crate::main_menu::main_menu_system::enter_main_menu_system;
+use crate::player_systems::bonfire::Bonfire;
+use crate::player_systems::player_components::Player;
+use crate::player_systems::player_powers::PlayerPowers;
+use crate::save_game::save_file::{SaveFile, SavedPlayer, SAVE_SLOT_COUNT};
+use crate::scripting::scripted_game_entity::GameData;
+use crate::GameStates;
+use bevy::prelude::*;
+
+pub mod migrations;
+pub mod save_file;
+
+pub struct SaveGamePlugin;
+
+impl Plugin for SaveGamePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SaveSlots>()
+            .init_resource::<ActiveSaveSlot>()
+            .add_observer(save_game_observer)
+            .add_systems(
+                OnEnter(GameStates::MainMenu),
+                refresh_save_slots_system.before(enter_main_menu_system),
+            );
+    }
+}
+
+/// Writes the current run into the active slot
+#[derive(Event)]
+pub struct SaveGame;
+
+/// Slot the current run saves into
+#[derive(Resource, Default)]
+pub struct ActiveSaveSlot(pub u32);
+
+/// Save picked in the main menu, applied to the player once it spawns
+#[derive(Resource)]
+pub struct LoadedSave(pub SavedPlayer);
+
+/// Saves found on disk, indexed by slot
+#[derive(Resource, Default)]
+pub struct SaveSlots(pub Vec<Option<SaveFile>>);
+
+impl SaveSlots {
+    pub fn read() -> Self {
+        Self(
+            (0..SAVE_SLOT_COUNT)
+                .map(|slot| {
+                    SaveFile::read(slot)
+                        .inspect_err(|e| error!("failed to read save slot {slot}: {e:?}"))
+                        .ok()
+                        .flatten()
+                })
+                .collect(),
+        )
+    }
+
+    /// Most recently written save
+    pub fn latest(&self) -> Option<(u32, &SaveFile)> {
+        self.saves().max_by_key(|(_, save)| save.saved_at)
+    }
+
+    pub fn saves(&self) -> impl Iterator<Item = (u32, &SaveFile)> {
+        self.0
+            .iter()
+            .enumerate()
+            .filter_map(|(slot, save)| Some((slot as u32, save.as_ref()?)))
+    }
+
+    /// Slot a new game saves into, the oldest save is overwritten once all slots are used
+    pub fn slot_for_new_game(&self) -> u32 {
+        if let Some(slot) = self.0.iter().position(Option::is_none) {
+            return slot as u32;
+        }
+
+        self.saves()
+            .min_by_key(|(_, save)| save.saved_at)
+            .map(|(slot, _)| slot)
+            .unwrap_or_default()
+    }
+}
+
+pub fn refresh_save_slots_system(mut save_slots: ResMut<SaveSlots>) {
+    *save_slots = SaveSlots::read();
+}
+
+pub fn save_game_observer(
+    _trigger: Trigger<SaveGame>,
+    game_data: Res<GameData>,
+    active_slot: Res<ActiveSaveSlot>,
+    mut save_slots: ResMut<SaveSlots>,
+    player: Query<(&Bonfire, &Health, PlayerPowers), With<Player>>,
+) {
+    let Ok((bonfire, health, powers)) = player.get_single() else {
+        return;
+    };
+
+    let save = SaveFile::new(
+        game_data.game_state.lock().unwrap().clone(),
+        SavedPlayer {
+            bonfire_level_index: bonfire.level_index,
+            bonfire_spawn_name: bonfire.spawn_name.clone(),
+            powers: powers.names(),
+            health: health.0.current,
+            max_health: health.0.max,
+        },
+    );
+
+    match save.write(active_slot.0) {
+        Ok(()) => {
+            info!("saved game to slot {}", active_slot.0);
+
+            if let Some(slot) = save_slots.0.get_mut(active_slot.0 as usize) {
+                *slot = Some(save);
+            }
+        }
+        Err(e) => error!("failed to save game to slot {}: {e:?}", active_slot.0),
+    }
+}
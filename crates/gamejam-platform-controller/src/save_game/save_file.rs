@@ -0,0 +1,125 @@
+use crate::save_game::migrations::{current_save_version, migrate_save};
+use crate::scripting::scripted_game_entity::GameState;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Version new saves are written with, older ones are upgraded by the migrations
+pub const SAVE_FILE_VERSION: u32 = current_save_version();
+/// Number of save slots offered in the main menu
+pub const SAVE_SLOT_COUNT: u32 = 3;
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct SaveFile {
+    pub version: u32,
+    /// Seconds since the unix epoch, picks the save "Continue" resumes
+    pub saved_at: u64,
+    /// Game data and per-entity state of every level visited
+    pub game_state: GameState,
+    pub player: SavedPlayer,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct SavedPlayer {
+    pub bonfire_level_index: u32,
+    pub bonfire_spawn_name: String,
+    /// Names of the granted powers, as used by `grant-player-power`
+    pub powers: Vec<String>,
+    pub health: u32,
+    pub max_health: u32,
+}
+
+impl SaveFile {
+    pub fn new(game_state: GameState, player: SavedPlayer) -> Self {
+        Self {
+            version: SAVE_FILE_VERSION,
+            saved_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|time| time.as_secs())
+                .unwrap_or_default(),
+            game_state,
+            player,
+        }
+    }
+
+    /// Reads the save in a slot, `None` if the slot is empty
+    pub fn read(slot: u32) -> anyhow::Result<Option<Self>> {
+        let path = slot_path(slot);
+
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        Ok(Some(Self::read_path(&path)?.0))
+    }
+
+    /// Reads and migrates a save file, also returning the version it was written with
+    pub fn read_path(path: &Path) -> anyhow::Result<(Self, u32)> {
+        Self::from_json(&fs::read(path)?)
+            .map_err(|e| e.context(format!("reading save {}", path.display())))
+    }
+
+    pub fn from_json(bytes: &[u8]) -> anyhow::Result<(Self, u32)> {
+        let mut save: serde_json::Value = serde_json::from_slice(bytes)?;
+        let original_version = migrate_save(&mut save)?;
+
+        Ok((serde_json::from_value(save)?, original_version))
+    }
+
+    pub fn write(&self, slot: u32) -> anyhow::Result<()> {
+        let path = slot_path(slot);
+        fs::create_dir_all(save_directory())?;
+
+        // Written next to the save first, so a crash mid-write can't eat the previous one
+        let temp_path = path.with_extension("json.tmp");
+        fs::write(&temp_path, serde_json::to_vec_pretty(self)?)?;
+        fs::rename(temp_path, path)?;
+
+        Ok(())
+    }
+}
+
+pub fn slot_path(slot: u32) -> PathBuf {
+    save_directory().join(format!("slot_{slot}.json"))
+}
+
+pub fn save_directory() -> PathBuf {
+    game_data_directory().join("saves")
+}
+
+/// Where the game keeps everything it persists for the player
+pub fn game_data_directory() -> PathBuf {
+    user_game_data_directory().unwrap_or_else(|| std::env::temp_dir().join("gamejam"))
+}
+
+/// The game data directory inside the user's own data directory, `None` when the environment
+/// names none and `game_data_directory` falls back to the shared temp directory
+pub fn user_game_data_directory() -> Option<PathBuf> {
+    platform_data_directory().map(|directory| directory.join("gamejam"))
+}
+
+fn platform_data_directory() -> Option<PathBuf> {
+    #[cfg(target_os = "windows")]
+    {
+        std::env::var_os("APPDATA").map(PathBuf::from)
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        std::env::var_os("HOME").map(|home| {
+            PathBuf::from(home)
+                .join("Library")
+                .join("Application Support")
+        })
+    }
+
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    {
+        std::env::var_os("XDG_DATA_HOME")
+            .map(PathBuf::from)
+            .or_else(|| {
+                std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share"))
+            })
+    }
+}
@@ -1,50 +1,58 @@
 use crate::scripting::scripted_game_entity::{
     EntityScript, GameData, GameEntityHost, GameEntityHostLinker, GameEntityState,
 };
+use anyhow::anyhow;
 use bevy::prelude::*;
+use bevy_wasmer_scripting::component_cache::ComponentCache;
 use bevy_wasmer_scripting::scripted_entity::WasmEngine;
-use bevy_wasmer_scripting::wasm_script_asset::WasmScriptModuleBytes;
+use bevy_wasmer_scripting::wasm_script_asset::{CompiledComponents, WasmScriptModuleBytes};
 use scripted_game_entity::exports::gamejam::game::entity_resource::StartupSettings;
 use scripted_game_entity::gamejam::game::game_host::add_to_linker;
 use scripted_game_entity::gamejam::game::game_host::Direction;
 use scripted_game_entity::gamejam::game::game_host::EntityUniform;
 use scripted_game_entity::GameEntityWorld;
+use std::collections::BTreeMap;
 use wasmtime::component::Linker;
 use wasmtime::Store;
 
 pub fn create_entity_script(
     entity: Entity,
     script_path: &str,
+    prototype_name: Option<String>,
+    state_key: Option<String>,
     engine: &Res<WasmEngine>,
+    component_cache: Option<&ComponentCache>,
     linker: &mut GameEntityHostLinker,
     asset_server: &AssetServer,
     game_data: &Res<GameData>,
-    wasm_scripts: &mut Assets<WasmScriptModuleBytes>,
+    wasm_scripts: &Assets<WasmScriptModuleBytes>,
+    compiled_components: &mut CompiledComponents,
     script_params: Option<Vec<String>>,
     position: Vec2,
-) -> impl Bundle {
-    let script: Handle<WasmScriptModuleBytes> = asset_server.load(script_path);
-    let script = wasm_scripts.get_mut(&script).unwrap();
+    fuel_per_call: u64,
+) -> anyhow::Result<EntityScript> {
+    let script_handle: Handle<WasmScriptModuleBytes> = asset_server.load(script_path);
+    let script = wasm_scripts
+        .get(&script_handle)
+        .ok_or_else(|| anyhow!("script {script_path} is not loaded"))?;
 
-    let bytes = script.aot_component_bytes.get_or_insert_with(|| {
-        wit_component::ComponentEncoder::default()
-            .module(script.wasm_module_bytes.as_slice())
-            .unwrap()
-            .encode()
-            .unwrap()
-    });
-
-    let component = script.aot_component.get_or_insert_with(|| {
-        wasmtime::component::Component::from_binary(&engine.0, bytes).unwrap()
-    });
+    // Modules are normally precompiled while loading, this only compiles ones that showed up later
+    let component = compiled_components.get_or_compile(
+        script_handle.id(),
+        script,
+        &engine.0,
+        component_cache,
+    )?;
 
     let mut store = Store::new(
         &engine.0,
         GameEntityState {
             host: GameEntityHost {
-                entity: Entity::PLACEHOLDER,
+                entity,
                 queued_commands: vec![],
                 game_state: game_data.game_state.clone(),
+                state_key: state_key.clone(),
+                transient_state: BTreeMap::new(),
                 player_uniform: EntityUniform {
                     health: None,
                     is_parrying: false,
@@ -57,6 +65,10 @@ pub fn create_entity_script(
                     position: (position.x, position.y),
                     facing: Direction::West,
                 },
+                spatial_query: game_data.spatial_query.clone(),
+                status_effects: game_data.status_effects.clone(),
+                event_subscriptions: game_data.event_subscriptions.clone(),
+                reserved_entities: game_data.reserved_entities.clone(),
             },
         },
     );
@@ -64,18 +76,83 @@ pub fn create_entity_script(
     let mut linker = &mut linker.0;
 
     let settings = StartupSettings {
-        params: script_params,
+        params: script_params.clone(),
         self_entity_id: entity.to_bits(),
     };
 
-    let entity = GameEntityWorld::instantiate(&mut store, &component, &linker).unwrap();
+    store.set_fuel(fuel_per_call)?;
+
+    let entity = GameEntityWorld::instantiate(&mut store, &component, &linker)?;
 
     let guest = entity.gamejam_game_entity_resource();
-    let entity_resource = guest.call_get_entity(&mut store, &settings).unwrap();
+    let entity_resource = guest.call_get_entity(&mut store, &settings)?;
 
-    EntityScript {
+    Ok(EntityScript {
         game_entity: entity,
         entity_resource,
         store,
+        script_path: script_path.to_string(),
+        state_key,
+        script_asset: script_handle.id(),
+        prototype_name,
+        script_params,
+        fuel_per_call,
+        suspended: false,
+        fault: None,
+        restart_count: 0,
+    })
+}
+
+/// Builds a fresh instance of an existing script on the same entity, using its original startup parameters.
+/// Once that succeeded, the commands the old instance still had queued are discarded.
+pub fn recreate_entity_script(
+    entity: Entity,
+    script: &mut EntityScript,
+    engine: &Res<WasmEngine>,
+    component_cache: Option<&ComponentCache>,
+    linker: &mut GameEntityHostLinker,
+    asset_server: &AssetServer,
+    game_data: &Res<GameData>,
+    wasm_scripts: &Assets<WasmScriptModuleBytes>,
+    compiled_components: &mut CompiledComponents,
+    position: Vec2,
+) -> anyhow::Result<EntityScript> {
+    // The new instance subscribes to whatever topics it wants from scratch, while being created
+    let old_topics = game_data
+        .event_subscriptions
+        .lock()
+        .unwrap()
+        .remove_entity(entity);
+
+    let recreated = create_entity_script(
+        entity,
+        &script.script_path,
+        script.prototype_name.clone(),
+        script.state_key.clone(),
+        engine,
+        component_cache,
+        linker,
+        asset_server,
+        game_data,
+        wasm_scripts,
+        compiled_components,
+        script.script_params.clone(),
+        position,
+        script.fuel_per_call,
+    );
+
+    if recreated.is_ok() {
+        // The old instance is replaced, its spawns never happen
+        script.discard_queued_commands();
+    } else {
+        // The old instance stays in place, so it gets its subscriptions back
+        let mut subscriptions = game_data.event_subscriptions.lock().unwrap();
+        subscriptions.remove_entity(entity);
+
+        for topic in old_topics {
+            subscriptions.subscribe(topic, entity);
+        }
     }
+
+    recreated
 }
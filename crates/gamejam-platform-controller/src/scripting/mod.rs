@@ -1,11 +1,19 @@
 use crate::scripting::script_entity_command_queue::scripted_entity_command_queue_system;
-use crate::scripting::scripted_game_entity::{game_entity_script_event_system, scripted_entity_uniform_system, setup_game_entity_script, tick_scripted_entity_system, GameData, ScriptEvent};
+use crate::scripting::script_event_subscriptions::script_removed_observer;
+#[cfg(feature = "hot-reload")]
+use crate::scripting::script_hot_reload::script_hot_reload_system;
+use crate::scripting::script_faults::{script_fault_system, ScriptFaultPolicy, ScriptFaulted};
+use crate::scripting::scripted_game_entity::{game_entity_script_event_system, reserve_script_entities_system, script_reservations_removed_observer, script_spatial_query_sync_system, script_status_effects_sync_system, scripted_entity_uniform_system, setup_game_entity_script, tick_scripted_entity_system, GameData, ScriptEvent};
 use crate::GameStates;
 use bevy::app::{App, FixedUpdate, Startup};
-use bevy::prelude::{in_state, IntoSystemConfigs, Plugin, Update};
+use bevy::prelude::{in_state, IntoSystemConfigs, Plugin};
 
 pub mod create_entity_script;
 pub mod script_entity_command_queue;
+pub mod script_event_subscriptions;
+pub mod script_faults;
+#[cfg(feature = "hot-reload")]
+pub mod script_hot_reload;
 pub mod scripted_game_entity;
 
 pub struct ScriptedGameEntityPlugin;
@@ -13,19 +21,33 @@ pub struct ScriptedGameEntityPlugin;
 impl Plugin for ScriptedGameEntityPlugin {
     fn build(&self, app: &mut App) {
         app.add_event::<ScriptEvent>()
+            .add_event::<ScriptFaulted>()
+            .init_resource::<ScriptFaultPolicy>()
+            .add_observer(script_removed_observer)
+            .add_observer(script_reservations_removed_observer)
             .add_systems(
                 FixedUpdate,
                 (
+                    reserve_script_entities_system,
+                    script_spatial_query_sync_system,
+                    script_status_effects_sync_system,
                     scripted_entity_uniform_system,
                     game_entity_script_event_system,
                     tick_scripted_entity_system,
                     scripted_entity_command_queue_system,
+                    script_fault_system,
                 )
                     .run_if(in_state(GameStates::GameLoop))
                     .chain(),
             )
             .add_systems(Startup, setup_game_entity_script)
             .insert_resource(GameData::default());
+
+        #[cfg(feature = "hot-reload")]
+        app.add_systems(
+            bevy::app::Update,
+            script_hot_reload_system.run_if(in_state(GameStates::GameLoop)),
+        );
     }
 }
 
@@ -1,18 +1,25 @@
 use crate::audio::audio_components::{AudioEffect, AudioMusic};
 use crate::combat::attackable::Attackable;
-use crate::combat::combat_components::{Boss, Health, ScheduledAttack};
+use crate::combat::combat_components::{Boss, Health, Resistances, ScheduledAttack};
+use crate::combat::knockback::WeightClass;
 use crate::combat::projectiles::Projectile;
+use crate::combat::status_effects::{apply_status_effect, StatusEffect};
 use crate::combat::Enemy;
 use crate::game_entities::file_formats::game_entity_definitions::{
     GameEntityDefinitionFile, GameEntityDefinitionFileHandle,
 };
 use crate::graphics::sprite_collection::SpriteCollection;
+use crate::ldtk_entities::game_entity::{spawn_game_entity, GameEntitySpawn};
 use crate::ldtk_entities::player_spawn::RequestedPlayerSpawn;
 use crate::movement_systems::movement_components::{EntityInput, FacingDirection, Input};
 use crate::player_systems::bonfire::Bonfire;
-use crate::player_systems::player_components::{Player, PowerupPogo, PowerupRoll};
+use crate::player_systems::player_components::Player;
+use crate::player_systems::player_powers::grant_player_power;
+use crate::save_game::SaveGame;
 use crate::scripting::create_entity_script::create_entity_script;
-use crate::scripting::scripted_game_entity::{EntityScript, GameData, GameEntityHostLinker, ScriptEvent};
+use crate::scripting::scripted_game_entity::{
+    EntityScript, GameData, GameEntityHostLinker, ScriptEvent, ScriptEventData,
+};
 use crate::timing::timer_system::add_timer_to_entity;
 use crate::timing::timing_component::{TimerComponent, TimerData};
 use crate::GameStates;
@@ -25,13 +32,14 @@ use bevy::hierarchy::BuildChildren;
 use bevy::log::{error, info};
 use bevy::math::Vec2;
 use bevy::prelude::{
-    Commands, Component, Entity, EventWriter, NextState, Query, Res, ResMut, Transform,
+    Commands, Component, Entity, EventWriter, NextState, Parent, Query, Res, ResMut, Transform,
     Vec3Swizzles, With,
 };
 use bevy::time::{Timer, TimerMode};
 use bevy_ecs_ldtk::LevelSelection;
-use bevy_wasmer_scripting::scripted_entity::WasmEngine;
-use bevy_wasmer_scripting::wasm_script_asset::WasmScriptModuleBytes;
+use bevy_wasmer_scripting::component_cache::ComponentCache;
+use bevy_wasmer_scripting::scripted_entity::{ScriptFuelBudget, WasmEngine};
+use bevy_wasmer_scripting::wasm_script_asset::{CompiledComponents, WasmScriptModuleBytes};
 use gamejam_bevy_components::Interactable;
 use scripted_game_entity::gamejam::game::game_host;
 use scripted_game_entity::gamejam::game::game_host::InsertableComponents;
@@ -60,6 +68,7 @@ pub enum EntityScriptCommand {
     Input(Input),
     Face(FacingDirection),
     ScheduleAttack(ScheduledAttack),
+    ApplyStatusEffect(Entity, StatusEffect),
     PlayMusic(String),
     PlaySound(String),
     GrantPlayerPower(String),
@@ -68,18 +77,35 @@ pub enum EntityScriptCommand {
         level_index: u32,
         spawn_name: String,
     },
+    SpawnEntity {
+        entity: Entity,
+        prototype_name: String,
+        position: Vec2,
+        script_params: Vec<String>,
+    },
+    SendMessage {
+        target: Entity,
+        payload: ScriptEventData,
+    },
 }
 
+/// Rounds of message delivery per frame, stops scripts that keep replying to each other
+const MAX_MESSAGE_ROUNDS: usize = 8;
+
 pub fn scripted_entity_command_queue_system(
     mut commands: Commands,
     sprites: Res<SpriteCollection>,
     asset_server: Res<AssetServer>,
     entity_db: Res<Assets<GameEntityDefinitionFile>>,
     entity_db_handle: Res<GameEntityDefinitionFileHandle>,
-    wasm_engine: Res<WasmEngine>,
+    (wasm_engine, component_cache): (Res<WasmEngine>, Option<Res<ComponentCache>>),
+    fuel_budget: Res<ScriptFuelBudget>,
     mut linker: ResMut<GameEntityHostLinker>,
     game_data: Res<GameData>,
-    mut wasm_scripts: ResMut<Assets<WasmScriptModuleBytes>>,
+    (wasm_scripts, mut compiled_components): (
+        Res<Assets<WasmScriptModuleBytes>>,
+        ResMut<CompiledComponents>,
+    ),
     mut level_select: ResMut<LevelSelection>,
     mut event_writer: EventWriter<ScriptEvent>,
     mut input_event_writer: EventWriter<EntityInput>,
@@ -89,6 +115,7 @@ pub fn scripted_entity_command_queue_system(
         &mut EntityScript,
         &mut TimerComponent,
         Option<&Transform>,
+        Option<&Parent>,
     )>,
     mut player: Query<(Entity, &mut Health), With<Player>>,
 ) {
@@ -97,27 +124,62 @@ pub fn scripted_entity_command_queue_system(
         .get(&entity_db_handle.0)
         .expect("missing entity db file");
 
-    for (entity, mut queue, mut timer, transform) in query.iter_mut() {
-        for cmd in queue.store.data_mut().host.queued_commands.drain(..) {
-            apply_command(
-                (player_entity.0, player_entity.1.as_mut()),
-                entity,
-                cmd,
-                asset_server.as_ref(),
-                &mut commands,
-                &sprites,
-                &mut level_select,
-                &mut event_writer,
-                &mut input_event_writer,
-                next_state.as_mut(),
-                timer.deref_mut(),
-                entity_db,
-                &wasm_engine,
-                linker.as_mut(),
-                &game_data,
-                &mut wasm_scripts,
-                &transform,
+    let mut messages = vec![];
+
+    for round in 0..MAX_MESSAGE_ROUNDS {
+        for (entity, mut queue, mut timer, transform, parent) in query.iter_mut() {
+            for cmd in queue.store.data_mut().host.queued_commands.drain(..) {
+                if let EntityScriptCommand::SendMessage { target, payload } = cmd {
+                    messages.push((entity, target, payload));
+                    continue;
+                }
+
+                apply_command(
+                    (player_entity.0, player_entity.1.as_mut()),
+                    entity,
+                    cmd,
+                    asset_server.as_ref(),
+                    &mut commands,
+                    &sprites,
+                    &mut level_select,
+                    &mut event_writer,
+                    &mut input_event_writer,
+                    next_state.as_mut(),
+                    timer.deref_mut(),
+                    entity_db,
+                    &wasm_engine,
+                    component_cache.as_deref(),
+                    &fuel_budget,
+                    linker.as_mut(),
+                    &game_data,
+                    &wasm_scripts,
+                    compiled_components.as_mut(),
+                    &transform,
+                    parent.map(|parent| parent.get()),
+                );
+            }
+        }
+
+        if messages.is_empty() {
+            break;
+        }
+
+        if round == MAX_MESSAGE_ROUNDS - 1 {
+            error!(
+                "dropping {} script messages, scripts keep messaging each other",
+                messages.len()
             );
+            break;
+        }
+
+        // Replies queue more commands, which the next round applies
+        for (sender, target, payload) in messages.drain(..) {
+            match query.get_mut(target) {
+                Ok((_, mut script, ..)) => script.receive_message(sender, payload.into()),
+                Err(_) => {
+                    info!("dropping message from {sender} to missing script entity {target}")
+                }
+            }
         }
     }
 }
@@ -136,10 +198,14 @@ fn apply_command(
     timer_component: &mut TimerComponent,
     entity_db: &GameEntityDefinitionFile,
     wasm_engine: &Res<WasmEngine>,
+    component_cache: Option<&ComponentCache>,
+    fuel_budget: &Res<ScriptFuelBudget>,
     linker: &mut GameEntityHostLinker,
     game_data: &Res<GameData>,
-    wasm_scripts: &mut ResMut<Assets<WasmScriptModuleBytes>>,
+    wasm_scripts: &Assets<WasmScriptModuleBytes>,
+    compiled_components: &mut CompiledComponents,
     transform: &Option<&Transform>,
+    parent: Option<Entity>,
 ) {
     let mut entity = commands.entity(entity_id);
 
@@ -183,6 +249,17 @@ fn apply_command(
             InsertableComponents::Boss => {
                 entity.insert(Boss);
             }
+            InsertableComponents::Resistances(resistances) => {
+                entity.insert(Resistances(
+                    resistances
+                        .into_iter()
+                        .map(|(damage_type, resistance)| (damage_type.into(), resistance))
+                        .collect(),
+                ));
+            }
+            InsertableComponents::WeightClass(weight_class) => {
+                entity.insert(WeightClass::from(weight_class));
+            }
         },
         EntityScriptCommand::PlayAnimation {
             sprite_name,
@@ -254,6 +331,9 @@ fn apply_command(
             let mut attack = commands.spawn(attack);
             attack.set_parent(entity_id);
         }
+        EntityScriptCommand::ApplyStatusEffect(target, effect) => {
+            apply_status_effect(commands, target, effect);
+        }
         EntityScriptCommand::PlayMusic(filename) => {
             commands.spawn((
                 AudioPlayer::new(asset_server.load(filename)),
@@ -268,23 +348,17 @@ fn apply_command(
                 AudioEffect,
             ));
         }
-        EntityScriptCommand::GrantPlayerPower(power) => match power.as_str() {
-            "roll" => {
-                commands.entity(player_entity.0).insert(PowerupRoll);
-            }
-            "pogo" => {
-                commands.entity(player_entity.0).insert(PowerupPogo);
-            }
-            power => {
+        EntityScriptCommand::GrantPlayerPower(power) => {
+            if !grant_player_power(&mut commands.entity(player_entity.0), &power) {
                 info!("Attempting to grant invalid power {power}")
             }
-        },
-        EntityScriptCommand::SpawnProjectile(velocity, offset, prototype, mut script_params) => {
+        }
+        EntityScriptCommand::SpawnProjectile(velocity, offset, prototype_name, mut script_params) => {
             let Some(transform) = transform else {
                 return;
             };
 
-            let prototype = entity_db.entities.get(&prototype).unwrap();
+            let prototype = entity_db.entities.get(&prototype_name).unwrap();
             let mut transform = **transform;
             transform.translation = transform.translation.add(&offset.extend(6.));
 
@@ -304,16 +378,29 @@ fn apply_command(
             let script = create_entity_script(
                 projectile_entity.id(),
                 prototype.script_path.as_ref().unwrap(),
+                Some(prototype_name.clone()),
+                None,
                 &wasm_engine,
+                component_cache,
                 linker,
                 asset_server,
                 game_data,
-                wasm_scripts.as_mut(),
+                wasm_scripts,
+                compiled_components,
                 Some(args),
                 transform.translation.xy(),
+                prototype.fuel_per_call.unwrap_or(fuel_budget.0),
             );
 
-            projectile_entity.insert(script);
+            match script {
+                Ok(script) => {
+                    projectile_entity.insert(script);
+                }
+                Err(e) => {
+                    error!("failed to create script for projectile {prototype_name}: {e:?}");
+                    projectile_entity.despawn();
+                }
+            }
         }
         EntityScriptCommand::SetBonfire {
             level_index,
@@ -329,7 +416,51 @@ fn apply_command(
 
             player_entity.1 .0.current = player_entity.1 .0.max;
             **level_select = LevelSelection::index(level_index as usize);
-            next_state.set(GameStates::LoadLevel)
+            next_state.set(GameStates::LoadLevel);
+
+            // Runs once the bonfire above is inserted
+            commands.trigger(SaveGame);
+        }
+        EntityScriptCommand::SpawnEntity {
+            entity: spawned_entity,
+            prototype_name,
+            position,
+            script_params,
+        } => {
+            let Some(prototype) = entity_db.entities.get(&prototype_name) else {
+                error!("script tried to spawn unknown prototype {prototype_name}");
+                commands.entity(spawned_entity).despawn();
+                return;
+            };
+
+            let mut spawn = GameEntitySpawn::from_prototype(&prototype_name, prototype);
+            spawn.script_params.extend(script_params);
+
+            // Spawn next to the spawner, so the entity goes away with its level
+            let z = transform.map(|t| t.translation.z).unwrap_or_default();
+            let mut spawned = commands.entity(spawned_entity);
+
+            if let Some(parent) = parent {
+                spawned.set_parent(parent);
+            }
+
+            spawn_game_entity(
+                &mut spawned,
+                spawn,
+                sprites,
+                wasm_engine,
+                component_cache,
+                fuel_budget,
+                linker,
+                game_data,
+                asset_server,
+                wasm_scripts,
+                compiled_components,
+                Transform::from_translation(position.extend(z)),
+            );
+        }
+        EntityScriptCommand::SendMessage { .. } => {
+            unreachable!("messages are delivered by scripted_entity_command_queue_system")
         }
     }
 }
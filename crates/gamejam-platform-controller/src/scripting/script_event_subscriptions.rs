@@ -0,0 +1,79 @@
+use crate::scripting::scripted_game_entity::{EntityScript, GameData};
+use bevy::ecs::entity::EntityHashSet;
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+
+/// Index of which entities' scripts receive events published on each topic
+#[derive(Default)]
+pub struct ScriptEventSubscriptions {
+    topics: HashMap<u32, EntityHashSet>,
+}
+
+impl ScriptEventSubscriptions {
+    pub fn subscribe(&mut self, topic: u32, entity: Entity) {
+        self.topics.entry(topic).or_default().insert(entity);
+    }
+
+    pub fn unsubscribe(&mut self, topic: u32, entity: Entity) {
+        if let Some(subscribers) = self.topics.get_mut(&topic) {
+            subscribers.remove(&entity);
+
+            if subscribers.is_empty() {
+                self.topics.remove(&topic);
+            }
+        }
+    }
+
+    pub fn subscribers(&self, topic: u32) -> Vec<Entity> {
+        self.topics
+            .get(&topic)
+            .map(|subscribers| subscribers.iter().copied().collect())
+            .unwrap_or_default()
+    }
+
+    /// Unsubscribes the entity from everything, returning the topics it was subscribed to
+    pub fn remove_entity(&mut self, entity: Entity) -> Vec<u32> {
+        let mut topics = vec![];
+
+        self.topics.retain(|topic, subscribers| {
+            if subscribers.remove(&entity) {
+                topics.push(*topic);
+            }
+
+            !subscribers.is_empty()
+        });
+
+        topics
+    }
+}
+
+pub fn script_removed_observer(trigger: Trigger<OnRemove, EntityScript>, game_data: Res<GameData>) {
+    game_data
+        .event_subscriptions
+        .lock()
+        .unwrap()
+        .remove_entity(trigger.entity());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn remove_entity_returns_its_topics_and_keeps_other_subscribers() {
+        let (a, b) = (Entity::from_raw(1), Entity::from_raw(2));
+        let mut subscriptions = ScriptEventSubscriptions::default();
+
+        subscriptions.subscribe(1, a);
+        subscriptions.subscribe(2, a);
+        subscriptions.subscribe(2, b);
+
+        let mut topics = subscriptions.remove_entity(a);
+        topics.sort();
+
+        assert_eq!(topics, vec![1, 2]);
+        assert!(subscriptions.subscribers(1).is_empty());
+        assert_eq!(subscriptions.subscribers(2), vec![b]);
+        assert!(subscriptions.remove_entity(a).is_empty());
+    }
+}
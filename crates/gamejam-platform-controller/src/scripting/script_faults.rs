@@ -0,0 +1,128 @@
+use crate::game_entities::file_formats::game_entity_definitions::{
+    GameEntityDefinitionFile, GameEntityDefinitionFileHandle,
+};
+use crate::scripting::create_entity_script::recreate_entity_script;
+use crate::scripting::scripted_game_entity::{EntityScript, GameData, GameEntityHostLinker};
+use bevy::prelude::*;
+use bevy_wasmer_scripting::component_cache::ComponentCache;
+use bevy_wasmer_scripting::scripted_entity::WasmEngine;
+use bevy_wasmer_scripting::wasm_script_asset::{CompiledComponents, WasmScriptModuleBytes};
+use serde::{Deserialize, Serialize};
+
+/// Restarts allowed before a repeatedly faulting script is quarantined instead
+const MAX_SCRIPT_RESTARTS: u32 = 3;
+
+/// What happens to an entity whose script trapped or ran out of fuel.
+/// The resource holds the default, prototypes can override it in `entities.toml`.
+#[derive(Resource, Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum ScriptFaultPolicy {
+    /// Keep the entity around, but never call into its script again
+    #[default]
+    Quarantine,
+    Despawn,
+    /// Re-instantiate the script from scratch on the same entity
+    Restart,
+}
+
+/// A failed guest call, recorded on the script until `script_fault_system` handles it
+#[derive(Debug, Clone)]
+pub struct ScriptFault {
+    pub call: String,
+    pub reason: String,
+}
+
+/// Sent whenever a script faults, after the fault policy has been applied
+#[derive(Event, Debug, Clone)]
+pub struct ScriptFaulted {
+    pub entity: Entity,
+    pub script_path: String,
+    pub prototype_name: Option<String>,
+    pub fault: ScriptFault,
+    pub policy: ScriptFaultPolicy,
+}
+
+pub fn script_fault_system(
+    mut commands: Commands,
+    default_policy: Res<ScriptFaultPolicy>,
+    entity_db: Res<Assets<GameEntityDefinitionFile>>,
+    entity_db_handle: Res<GameEntityDefinitionFileHandle>,
+    engine: Res<WasmEngine>,
+    component_cache: Option<Res<ComponentCache>>,
+    mut linker: ResMut<GameEntityHostLinker>,
+    asset_server: Res<AssetServer>,
+    game_data: Res<GameData>,
+    wasm_scripts: Res<Assets<WasmScriptModuleBytes>>,
+    mut compiled_components: ResMut<CompiledComponents>,
+    mut faulted_writer: EventWriter<ScriptFaulted>,
+    mut scripts: Query<(Entity, &mut EntityScript, Option<&Transform>)>,
+) {
+    let entity_db = entity_db.get(&entity_db_handle.0);
+
+    for (entity, mut script, transform) in scripts.iter_mut() {
+        if script.fault.is_none() {
+            continue;
+        }
+
+        let fault = script.fault.take().unwrap();
+
+        let mut policy = script
+            .prototype_name
+            .as_ref()
+            .and_then(|name| entity_db?.entities.get(name)?.fault_policy)
+            .unwrap_or(*default_policy);
+
+        if policy == ScriptFaultPolicy::Restart && script.restart_count >= MAX_SCRIPT_RESTARTS {
+            warn!(
+                "script {} on entity {entity} keeps faulting, quarantining it",
+                script.display_name()
+            );
+            policy = ScriptFaultPolicy::Quarantine;
+        }
+
+        match policy {
+            ScriptFaultPolicy::Quarantine => {}
+            ScriptFaultPolicy::Despawn => {
+                commands.entity(entity).despawn_recursive();
+            }
+            ScriptFaultPolicy::Restart => {
+                let position = transform
+                    .map(|t| t.translation.truncate())
+                    .unwrap_or_default();
+
+                match recreate_entity_script(
+                    entity,
+                    &mut script,
+                    &engine,
+                    component_cache.as_deref(),
+                    linker.as_mut(),
+                    asset_server.as_ref(),
+                    &game_data,
+                    &wasm_scripts,
+                    compiled_components.as_mut(),
+                    position,
+                ) {
+                    Ok(mut restarted) => {
+                        restarted.restart_count = script.restart_count + 1;
+                        *script = restarted;
+                    }
+                    Err(e) => {
+                        error!(
+                            "failed to restart script {} on entity {entity}: {e:?}",
+                            script.display_name()
+                        );
+                        policy = ScriptFaultPolicy::Quarantine;
+                    }
+                }
+            }
+        }
+
+        faulted_writer.send(ScriptFaulted {
+            entity,
+            script_path: script.script_path.clone(),
+            prototype_name: script.prototype_name.clone(),
+            fault,
+            policy,
+        });
+    }
+}
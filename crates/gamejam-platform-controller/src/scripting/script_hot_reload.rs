@@ -0,0 +1,79 @@
+use crate::scripting::create_entity_script::recreate_entity_script;
+use crate::scripting::scripted_game_entity::{EntityScript, GameData, GameEntityHostLinker};
+use bevy::prelude::*;
+use bevy_wasmer_scripting::component_cache::ComponentCache;
+use bevy_wasmer_scripting::scripted_entity::WasmEngine;
+use bevy_wasmer_scripting::wasm_script_asset::{CompiledComponents, WasmScriptModuleBytes};
+
+/// Re-instantiates every live script built from a `.wasm` module that changed on disk.
+/// The entity keeps its components (transform, timers etc.), only the guest state starts over.
+/// Modified events for modules whose bytes are unchanged are ignored.
+pub fn script_hot_reload_system(
+    mut asset_events: EventReader<AssetEvent<WasmScriptModuleBytes>>,
+    engine: Res<WasmEngine>,
+    component_cache: Option<Res<ComponentCache>>,
+    mut linker: ResMut<GameEntityHostLinker>,
+    asset_server: Res<AssetServer>,
+    game_data: Res<GameData>,
+    wasm_scripts: Res<Assets<WasmScriptModuleBytes>>,
+    mut compiled_components: ResMut<CompiledComponents>,
+    mut scripts: Query<(Entity, &mut EntityScript, Option<&Transform>)>,
+) {
+    for event in asset_events.read() {
+        let AssetEvent::Modified { id } = event else {
+            continue;
+        };
+
+        let Some(module) = wasm_scripts.get(*id) else {
+            continue;
+        };
+
+        if !compiled_components.is_outdated(*id, module) {
+            continue;
+        }
+
+        if let Err(e) =
+            compiled_components.get_or_compile(*id, module, &engine.0, component_cache.as_deref())
+        {
+            error!("failed to compile reloaded script module: {e:?}");
+            continue;
+        }
+
+        for (entity, mut script, transform) in scripts.iter_mut() {
+            if script.script_asset != *id {
+                continue;
+            }
+
+            let position = transform
+                .map(|t| t.translation.truncate())
+                .unwrap_or_default();
+
+            match recreate_entity_script(
+                entity,
+                &mut script,
+                &engine,
+                component_cache.as_deref(),
+                linker.as_mut(),
+                asset_server.as_ref(),
+                &game_data,
+                &wasm_scripts,
+                compiled_components.as_mut(),
+                position,
+            ) {
+                Ok(reloaded) => {
+                    info!(
+                        "reloaded script {} on entity {entity}",
+                        script.display_name()
+                    );
+                    *script = reloaded;
+                }
+                Err(e) => {
+                    error!(
+                        "failed to reload script {} on entity {entity}, keeping the old instance: {e:?}",
+                        script.display_name()
+                    );
+                }
+            }
+        }
+    }
+}
@@ -1,37 +1,213 @@
-use crate::combat::combat_components::ScheduledAttack;
+use crate::combat::combat_components::{DamageType, ScheduledAttack};
+use crate::combat::knockback::{KnockbackDecay, KnockbackProfile, WeightClass};
+use crate::combat::status_effects::{StatusEffect, StatusEffectKind, StatusEffects, Stunned};
 use crate::ldtk_entities::interactable::{InteractableInRange, Interacted};
 use crate::movement_systems::movement_components::{FacingDirection, Input};
-use crate::player_systems::player_components::Player;
+use crate::player_systems::player_components::{Parrying, Player};
 use crate::scripting::script_entity_command_queue::{EntityScriptCommand, TickingEntity};
+use crate::scripting::script_event_subscriptions::ScriptEventSubscriptions;
+use crate::scripting::script_faults::ScriptFault;
 use crate::scripting::scripted_game_entity::game_host::Vector;
-use bevy::log::info;
-use bevy::math::Vec2;
+use avian2d::prelude::{Collider, ShapeCastConfig, SpatialQueryFilter, SpatialQueryPipeline};
+use bevy::log::{error, info};
+use bevy::math::{Dir2, Vec2};
 use bevy::prelude::{
     Commands, Component, Entity, Event, EventReader, OnAdd, Query, Res, Resource, Time, Transform,
-    Trigger, With,
+    Trigger, With, Without,
 };
 use bevy::time::TimerMode;
+use bevy::asset::AssetId;
 use bevy_wasmer_scripting::scripted_entity::WasmEngine;
-use scripted_game_entity::exports::gamejam::game::entity_resource::EntityEvent;
+use bevy_wasmer_scripting::wasm_script_asset::WasmScriptModuleBytes;
+use scripted_game_entity::exports::gamejam::game::entity_resource::{EntityEvent, GuestGameEntity};
 use scripted_game_entity::gamejam::game::game_host::{self, EntityUniform};
 use scripted_game_entity::gamejam::game::game_host::{add_to_linker, Direction};
 use scripted_game_entity::gamejam::game::game_host::{Host, InsertableComponents};
+use scripted_game_entity::gamejam::game::game_host::{QueryHit, QueryShape, StateValue};
 use scripted_game_entity::GameEntityWorld;
+use serde::{Deserialize, Serialize};
+use bevy::utils::HashMap;
 use std::collections::BTreeMap;
-use std::sync::{Arc, Mutex};
+use std::sync::{Arc, Mutex, RwLock, RwLockReadGuard};
 use std::time::Duration;
 use wasmtime::component::{Linker, ResourceAny};
-use wasmtime::{AsContextMut, Engine, Store};
+use wasmtime::{Engine, Store, Trap};
 
-#[derive(Default)]
+#[derive(Default, Clone, Serialize, Deserialize)]
 pub struct GameState {
     pub strings: BTreeMap<String, String>,
     pub ints: BTreeMap<String, i32>,
+    /// Script state of entities placed in LDtk, keyed by entity iid
+    pub entity_states: BTreeMap<String, BTreeMap<String, ScriptStateValue>>,
+}
+
+/// Value of a per-entity script state entry, mirrors `state-value` in `game_host.wit`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ScriptStateValue {
+    String(String),
+    Int(i32),
+    Float(f32),
+    Bool(bool),
+}
+
+impl From<StateValue> for ScriptStateValue {
+    fn from(value: StateValue) -> Self {
+        match value {
+            StateValue::String(value) => ScriptStateValue::String(value),
+            StateValue::Int(value) => ScriptStateValue::Int(value),
+            StateValue::Float(value) => ScriptStateValue::Float(value),
+            StateValue::Bool(value) => ScriptStateValue::Bool(value),
+        }
+    }
+}
+
+impl From<game_host::DamageType> for DamageType {
+    fn from(value: game_host::DamageType) -> Self {
+        match value {
+            game_host::DamageType::Physical => DamageType::Physical,
+            game_host::DamageType::Fire => DamageType::Fire,
+            game_host::DamageType::Poison => DamageType::Poison,
+            game_host::DamageType::Frost => DamageType::Frost,
+        }
+    }
+}
+
+impl From<game_host::StatusEffect> for StatusEffect {
+    fn from(value: game_host::StatusEffect) -> Self {
+        StatusEffect {
+            kind: match value.kind {
+                game_host::StatusEffectKind::Burn => StatusEffectKind::Burn,
+                game_host::StatusEffectKind::Poison => StatusEffectKind::Poison,
+                game_host::StatusEffectKind::Stun => StatusEffectKind::Stun,
+                game_host::StatusEffectKind::Slow => StatusEffectKind::Slow,
+            },
+            duration_millis: value.duration_millis,
+            magnitude: value.magnitude,
+        }
+    }
+}
+
+impl From<StatusEffect> for game_host::StatusEffect {
+    fn from(value: StatusEffect) -> Self {
+        game_host::StatusEffect {
+            kind: match value.kind {
+                StatusEffectKind::Burn => game_host::StatusEffectKind::Burn,
+                StatusEffectKind::Poison => game_host::StatusEffectKind::Poison,
+                StatusEffectKind::Stun => game_host::StatusEffectKind::Stun,
+                StatusEffectKind::Slow => game_host::StatusEffectKind::Slow,
+            },
+            duration_millis: value.duration_millis,
+            magnitude: value.magnitude,
+        }
+    }
 }
 
+impl From<game_host::KnockbackProfile> for KnockbackProfile {
+    fn from(value: game_host::KnockbackProfile) -> Self {
+        KnockbackProfile {
+            angle_degrees: Some(value.angle_degrees),
+            impulse: value.impulse,
+            duration_millis: value.duration_millis,
+            decay: match value.decay {
+                game_host::KnockbackDecay::Linear => KnockbackDecay::Linear,
+                game_host::KnockbackDecay::Quadratic => KnockbackDecay::Quadratic,
+                game_host::KnockbackDecay::Constant => KnockbackDecay::Constant,
+            },
+        }
+    }
+}
+
+impl From<game_host::WeightClass> for WeightClass {
+    fn from(value: game_host::WeightClass) -> Self {
+        match value {
+            game_host::WeightClass::Light => WeightClass::Light,
+            game_host::WeightClass::Medium => WeightClass::Medium,
+            game_host::WeightClass::Heavy => WeightClass::Heavy,
+        }
+    }
+}
+
+impl From<ScriptStateValue> for StateValue {
+    fn from(value: ScriptStateValue) -> Self {
+        match value {
+            ScriptStateValue::String(value) => StateValue::String(value),
+            ScriptStateValue::Int(value) => StateValue::Int(value),
+            ScriptStateValue::Float(value) => StateValue::Float(value),
+            ScriptStateValue::Bool(value) => StateValue::Bool(value),
+        }
+    }
+}
+
+/// Entities scripts can spawn per frame, before the pool is refilled
+const RESERVED_ENTITY_POOL_SIZE: usize = 16;
+/// The pool grows whenever scripts run dry, up to this many entities
+const MAX_RESERVED_ENTITY_POOL_SIZE: usize = 1024;
+
 #[derive(Resource, Default)]
 pub struct GameData {
     pub game_state: Arc<Mutex<GameState>>,
+    pub spatial_query: Arc<ScriptSpatialQuery>,
+    /// Status effects of every affected entity, refreshed every frame
+    pub status_effects: Arc<RwLock<HashMap<Entity, Vec<StatusEffect>>>>,
+    pub event_subscriptions: Arc<Mutex<ScriptEventSubscriptions>>,
+    pub reserved_entities: Arc<Mutex<ReservedEntities>>,
+}
+
+/// Entities reserved ahead of time, so `spawn-entity` can hand out ids before the spawn happens
+pub struct ReservedEntities {
+    available: Vec<Entity>,
+    pool_size: usize,
+    /// Whether a script found the pool empty since the last refill
+    ran_dry: bool,
+}
+
+impl Default for ReservedEntities {
+    fn default() -> Self {
+        Self {
+            available: vec![],
+            pool_size: RESERVED_ENTITY_POOL_SIZE,
+            ran_dry: false,
+        }
+    }
+}
+
+impl ReservedEntities {
+    pub fn take(&mut self) -> Option<Entity> {
+        let entity = self.available.pop();
+        self.ran_dry |= entity.is_none();
+
+        entity
+    }
+
+    /// Hands back an entity whose spawn never happened, so it doesn't linger as an empty entity
+    pub fn release(&mut self, entity: Entity) {
+        self.available.push(entity);
+    }
+
+    /// Tops the pool up, doubling it first if scripts ran dry since the last refill
+    fn refill(&mut self, mut reserve: impl FnMut() -> Entity) {
+        if std::mem::take(&mut self.ran_dry) {
+            self.pool_size = (self.pool_size * 2).min(MAX_RESERVED_ENTITY_POOL_SIZE);
+        }
+
+        while self.available.len() < self.pool_size {
+            self.available.push(reserve());
+        }
+    }
+}
+
+/// Copy of the physics query pipeline, since scripts can't reach the ECS during calls.
+/// Refreshed on every fixed tick scripts run in, so queries see the world as of the start
+/// of the tick.
+#[derive(Default)]
+pub struct ScriptSpatialQuery {
+    pipeline: RwLock<SpatialQueryPipeline>,
+}
+
+impl ScriptSpatialQuery {
+    pub fn pipeline(&self) -> RwLockReadGuard<'_, SpatialQueryPipeline> {
+        self.pipeline.read().unwrap()
+    }
 }
 
 /// Generic game entity script component.
@@ -41,14 +217,86 @@ pub struct EntityScript {
     pub game_entity: GameEntityWorld,
     pub entity_resource: ResourceAny,
     pub store: Store<GameEntityState>,
+    pub script_path: String,
+    /// LDtk iid of the entity, key of its persistent script state
+    pub state_key: Option<String>,
+    /// The module this script was instantiated from, used to find scripts to hot-reload
+    pub script_asset: AssetId<WasmScriptModuleBytes>,
+    pub prototype_name: Option<String>,
+    /// Startup parameters, kept around so the script can be restarted
+    pub script_params: Option<Vec<String>>,
+    /// Fuel the store is refilled with before every guest call
+    pub fuel_per_call: u64,
+    /// Set once the script faulted, after which it is never called again
+    pub suspended: bool,
+    /// Fault raised by the last failing guest call, handled by `script_fault_system`
+    pub fault: Option<ScriptFault>,
+    pub restart_count: u32,
 }
 
 pub struct GameEntityHost {
     pub entity: Entity,
     pub queued_commands: Vec<EntityScriptCommand>,
     pub game_state: Arc<Mutex<GameState>>,
+    pub state_key: Option<String>,
+    /// State of entities not placed in LDtk, gone once the script goes away
+    pub transient_state: BTreeMap<String, ScriptStateValue>,
     pub player_uniform: EntityUniform,
     pub self_uniform: EntityUniform,
+    pub spatial_query: Arc<ScriptSpatialQuery>,
+    pub status_effects: Arc<RwLock<HashMap<Entity, Vec<StatusEffect>>>>,
+    pub event_subscriptions: Arc<Mutex<ScriptEventSubscriptions>>,
+    pub reserved_entities: Arc<Mutex<ReservedEntities>>,
+}
+
+impl GameEntityHost {
+    /// Drops the queued commands, handing back the entities reserved for spawns among them
+    fn discard_queued_commands(&mut self) {
+        let mut reserved_entities = self.reserved_entities.lock().unwrap();
+
+        for command in self.queued_commands.drain(..) {
+            if let EntityScriptCommand::SpawnEntity { entity, .. } = command {
+                reserved_entities.release(entity);
+            }
+        }
+    }
+
+    fn query_filter(&self, layer_mask: u32) -> SpatialQueryFilter {
+        SpatialQueryFilter::from_mask(layer_mask).with_excluded_entities([self.entity])
+    }
+
+    fn queue_attack(
+        &mut self,
+        delay: u32,
+        damage: u32,
+        damage_type: DamageType,
+        effects: Vec<StatusEffect>,
+        knockback: KnockbackProfile,
+        point: (f32, f32),
+        vector: (f32, f32),
+    ) {
+        self.queued_commands
+            .push(EntityScriptCommand::ScheduleAttack(ScheduledAttack {
+                attacker: Entity::PLACEHOLDER,
+                delay: bevy::prelude::Timer::from_seconds(
+                    Duration::from_millis(delay as u64).as_secs_f32(),
+                    TimerMode::Once,
+                ),
+                damage,
+                knockback,
+                origin: Vec2::new(point.0, point.1),
+                vector: Vec2::new(vector.0, vector.1),
+                damage_type,
+                effects,
+            }))
+    }
+}
+
+fn query_shape_collider(shape: QueryShape) -> Collider {
+    match shape {
+        QueryShape::Circle(radius) => Collider::circle(radius),
+        QueryShape::Rectangle(size) => Collider::rectangle(size.x, size.y),
+    }
 }
 
 pub struct GameEntityState {
@@ -65,57 +313,121 @@ pub fn setup_game_entity_script(mut commands: Commands, engine: Res<WasmEngine>)
 }
 
 impl EntityScript {
-    pub fn animation_finished(&mut self, animation_name: &str) {
-        let guest = self.game_entity.gamejam_game_entity_resource();
-        let entity_resource_guest = guest.game_entity();
+    pub fn tick(&mut self, delta_t: f32) {
+        self.call_guest("tick", |guest, store, resource| {
+            guest.call_tick(store, resource, delta_t)
+        });
+    }
 
-        entity_resource_guest
-            .call_animation_finished(
-                self.store.as_context_mut(),
-                self.entity_resource,
-                animation_name,
-            )
-            .unwrap();
+    pub fn animation_finished(&mut self, animation_name: &str) {
+        self.call_guest("animation-finished", |guest, store, resource| {
+            guest.call_animation_finished(store, resource, animation_name)
+        });
     }
 
     pub fn interact(&mut self) {
-        let guest = self.game_entity.gamejam_game_entity_resource();
-        let entity_resource_guest = guest.game_entity();
-
-        entity_resource_guest
-            .call_interacted(self.store.as_context_mut(), self.entity_resource)
-            .unwrap();
+        self.call_guest("interacted", |guest, store, resource| {
+            guest.call_interacted(store, resource)
+        });
     }
 
     pub fn attacked(&mut self) {
-        let guest = self.game_entity.gamejam_game_entity_resource();
-        let entity_resource_guest = guest.game_entity();
-
-        entity_resource_guest
-            .call_attacked(self.store.as_context_mut(), self.entity_resource)
-            .unwrap();
+        self.call_guest("attacked", |guest, store, resource| {
+            guest.call_attacked(store, resource)
+        });
     }
 
     pub fn timer_callback(&mut self, timer: u32) {
-        let guest = self.game_entity.gamejam_game_entity_resource();
-        let entity_resource_guest = guest.game_entity();
-
-        entity_resource_guest
-            .call_timer_callback(self.store.as_context_mut(), self.entity_resource, timer)
-            .unwrap();
+        self.call_guest("timer-callback", |guest, store, resource| {
+            guest.call_timer_callback(store, resource, timer)
+        });
     }
 
     pub fn killed(&mut self) {
         self.dispatch_entity_event(EntityEvent::Killed);
     }
 
+    pub fn parried(&mut self) {
+        self.dispatch_entity_event(EntityEvent::Parried);
+    }
+
     pub fn dispatch_entity_event(&mut self, event: EntityEvent) {
+        self.call_guest("receive-entity-event", |guest, store, resource| {
+            guest.call_receive_entity_event(store, resource, event)
+        });
+    }
+
+    pub fn receive_event(&mut self, event: game_host::Event) {
+        self.call_guest("receive-event", |guest, store, resource| {
+            guest.call_receive_event(store, resource, &event)
+        });
+    }
+
+    pub fn receive_message(&mut self, sender: Entity, payload: game_host::EventData) {
+        self.call_guest("receive-message", |guest, store, resource| {
+            guest.call_receive_message(store, resource, sender.to_bits(), &payload)
+        });
+    }
+
+    pub fn display_name(&self) -> &str {
+        self.prototype_name.as_deref().unwrap_or(&self.script_path)
+    }
+
+    /// Drops the commands the script queued but that weren't applied yet,
+    /// handing back the entities reserved for spawns among them
+    pub fn discard_queued_commands(&mut self) {
+        self.store.data_mut().host.discard_queued_commands();
+    }
+
+    /// Refuels the store and performs a guest call.
+    /// A trapping script, or one running out of fuel, is suspended and its fault recorded
+    /// instead of taking down the host.
+    fn call_guest<T>(
+        &mut self,
+        call_name: &str,
+        call: impl FnOnce(
+            GuestGameEntity<'_>,
+            &mut Store<GameEntityState>,
+            ResourceAny,
+        ) -> wasmtime::Result<T>,
+    ) -> Option<T> {
+        if self.suspended {
+            return None;
+        }
+
+        if let Err(e) = self.store.set_fuel(self.fuel_per_call) {
+            error!("failed to refuel script {}: {e:?}", self.display_name());
+            return None;
+        }
+
         let guest = self.game_entity.gamejam_game_entity_resource();
-        let entity_resource_guest = guest.game_entity();
 
-        entity_resource_guest
-            .call_receive_entity_event(self.store.as_context_mut(), self.entity_resource, event)
-            .unwrap();
+        match call(guest.game_entity(), &mut self.store, self.entity_resource) {
+            Ok(value) => Some(value),
+            Err(e) => {
+                let reason = if matches!(e.downcast_ref::<Trap>(), Some(Trap::OutOfFuel)) {
+                    format!("exceeded its budget of {} fuel", self.fuel_per_call)
+                } else {
+                    format!("{e:?}")
+                };
+
+                error!(
+                    "script {} on entity {} faulted in {call_name}: {reason}",
+                    self.display_name(),
+                    self.store.data().host.entity
+                );
+
+                // Commands queued by an aborted call may leave the entity half-updated
+                self.discard_queued_commands();
+                self.suspended = true;
+                self.fault = Some(ScriptFault {
+                    call: call_name.to_string(),
+                    reason,
+                });
+
+                None
+            }
+        }
     }
 }
 
@@ -127,12 +439,31 @@ impl Host for GameEntityHost {
         self.queued_commands
             .push(EntityScriptCommand::PublishEvent(ScriptEvent {
                 topic: evt.topic,
-                data: match evt.data {
-                    game_host::EventData::Trigger(topic) => ScriptEventData::Trigger(topic),
-                },
+                data: evt.data.into(),
             }));
     }
 
+    fn subscribe(&mut self, topic: u32) {
+        self.event_subscriptions
+            .lock()
+            .unwrap()
+            .subscribe(topic, self.entity);
+    }
+
+    fn unsubscribe(&mut self, topic: u32) {
+        self.event_subscriptions
+            .lock()
+            .unwrap()
+            .unsubscribe(topic, self.entity);
+    }
+
+    fn send_message(&mut self, target_entity_id: u64, payload: game_host::EventData) {
+        self.queued_commands.push(EntityScriptCommand::SendMessage {
+            target: entity_from_script(target_entity_id),
+            payload: payload.into(),
+        });
+    }
+
     fn set_ticking(&mut self, ticking: bool, distance: Option<f32>) {
         self.queued_commands
             .push(EntityScriptCommand::ToggleTicking((ticking, distance)));
@@ -153,6 +484,38 @@ impl Host for GameEntityHost {
         self.game_state.lock().unwrap().ints.insert(key, value)
     }
 
+    fn get_self_state(&mut self, key: String) -> Option<StateValue> {
+        let value = match &self.state_key {
+            Some(state_key) => self
+                .game_state
+                .lock()
+                .unwrap()
+                .entity_states
+                .get(state_key)?
+                .get(&key)
+                .cloned(),
+            None => self.transient_state.get(&key).cloned(),
+        };
+
+        value.map(Into::into)
+    }
+
+    fn set_self_state(&mut self, key: String, value: StateValue) -> Option<StateValue> {
+        let previous = match &self.state_key {
+            Some(state_key) => self
+                .game_state
+                .lock()
+                .unwrap()
+                .entity_states
+                .entry(state_key.clone())
+                .or_default()
+                .insert(key, value.into()),
+            None => self.transient_state.insert(key, value.into()),
+        };
+
+        previous.map(Into::into)
+    }
+
     fn remove_component(&mut self, path: String) {
         self.queued_commands
             .push(EntityScriptCommand::RemoveReflectComponent(path));
@@ -219,8 +582,129 @@ impl Host for GameEntityHost {
         self.self_uniform
     }
 
-    fn can_see_player(&mut self) -> bool {
-        todo!()
+    fn can_see_player(
+        &mut self,
+        max_distance: Option<f32>,
+        view_cone_degrees: Option<f32>,
+    ) -> bool {
+        let self_position = Vec2::from(self.self_uniform.position);
+        let to_player = Vec2::from(self.player_uniform.position) - self_position;
+
+        if max_distance.is_some_and(|max_distance| to_player.length() > max_distance) {
+            return false;
+        }
+
+        // Standing right on top of the player
+        let Ok(direction) = Dir2::new(to_player) else {
+            return true;
+        };
+
+        if let Some(view_cone_degrees) = view_cone_degrees {
+            let facing = match self.self_uniform.facing {
+                Direction::West => Vec2::NEG_X,
+                _ => Vec2::X,
+            };
+
+            if facing.angle_to(to_player).abs().to_degrees() > view_cone_degrees / 2. {
+                return false;
+            }
+        }
+
+        // Only walls block the view, cast last since it is the expensive check
+        self.spatial_query
+            .pipeline()
+            .cast_ray(
+                self_position,
+                direction,
+                to_player.length(),
+                false,
+                &self.query_filter(0b00100),
+            )
+            .is_none()
+    }
+
+    fn raycast(
+        &mut self,
+        origin: Vector,
+        direction: Vector,
+        max_distance: f32,
+        layer_mask: u32,
+    ) -> Option<QueryHit> {
+        let origin = Vec2::new(origin.x, origin.y);
+        let direction = Dir2::new(Vec2::new(direction.x, direction.y)).ok()?;
+
+        let hit = self.spatial_query.pipeline().cast_ray(
+            origin,
+            direction,
+            max_distance,
+            true,
+            &self.query_filter(layer_mask),
+        )?;
+
+        let point = origin + direction * hit.distance;
+
+        Some(QueryHit {
+            entity_id: hit.entity.to_bits(),
+            point: Vector {
+                x: point.x,
+                y: point.y,
+            },
+            normal: Vector {
+                x: hit.normal.x,
+                y: hit.normal.y,
+            },
+            distance: hit.distance,
+        })
+    }
+
+    fn shape_cast(
+        &mut self,
+        shape: QueryShape,
+        origin: Vector,
+        direction: Vector,
+        max_distance: f32,
+        layer_mask: u32,
+    ) -> Option<QueryHit> {
+        let direction = Dir2::new(Vec2::new(direction.x, direction.y)).ok()?;
+
+        let hit = self.spatial_query.pipeline().cast_shape(
+            &query_shape_collider(shape),
+            Vec2::new(origin.x, origin.y),
+            0.,
+            direction,
+            &ShapeCastConfig {
+                max_distance,
+                ..ShapeCastConfig::default()
+            },
+            &self.query_filter(layer_mask),
+        )?;
+
+        Some(QueryHit {
+            entity_id: hit.entity.to_bits(),
+            point: Vector {
+                x: hit.point1.x,
+                y: hit.point1.y,
+            },
+            normal: Vector {
+                x: hit.normal1.x,
+                y: hit.normal1.y,
+            },
+            distance: hit.distance,
+        })
+    }
+
+    fn overlap(&mut self, shape: QueryShape, position: Vector, layer_mask: u32) -> Vec<u64> {
+        self.spatial_query
+            .pipeline()
+            .shape_intersections(
+                &query_shape_collider(shape),
+                Vec2::new(position.x, position.y),
+                0.,
+                &self.query_filter(layer_mask),
+            )
+            .into_iter()
+            .map(|entity| entity.to_bits())
+            .collect()
     }
 
     fn send_input(&mut self, input: game_host::Input) {
@@ -240,18 +724,53 @@ impl Host for GameEntityHost {
         point: (f32, f32),
         vector: (f32, f32),
     ) {
+        self.queue_attack(
+            delay,
+            damage,
+            DamageType::Physical,
+            vec![],
+            KnockbackProfile::from_force(force),
+            point,
+            vector,
+        )
+    }
+
+    fn schedule_typed_attack(
+        &mut self,
+        delay: u32,
+        damage: u32,
+        damage_type: game_host::DamageType,
+        effects: Vec<game_host::StatusEffect>,
+        knockback: game_host::KnockbackProfile,
+        point: (f32, f32),
+        vector: (f32, f32),
+    ) {
+        self.queue_attack(
+            delay,
+            damage,
+            damage_type.into(),
+            effects.into_iter().map(StatusEffect::from).collect(),
+            knockback.into(),
+            point,
+            vector,
+        )
+    }
+
+    fn apply_status_effect(&mut self, entity_id: u64, effect: game_host::StatusEffect) {
         self.queued_commands
-            .push(EntityScriptCommand::ScheduleAttack(ScheduledAttack {
-                attacker: Entity::PLACEHOLDER,
-                delay: bevy::prelude::Timer::from_seconds(
-                    Duration::from_millis(delay as u64).as_secs_f32(),
-                    TimerMode::Once,
-                ),
-                damage,
-                force,
-                origin: Vec2::new(point.0, point.1),
-                vector: Vec2::new(vector.0, vector.1),
-            }))
+            .push(EntityScriptCommand::ApplyStatusEffect(
+                entity_from_script(entity_id),
+                effect.into(),
+            ))
+    }
+
+    fn get_status_effects(&mut self, entity_id: u64) -> Vec<game_host::StatusEffect> {
+        self.status_effects
+            .read()
+            .unwrap()
+            .get(&entity_from_script(entity_id))
+            .map(|effects| effects.iter().copied().map(Into::into).collect())
+            .unwrap_or_default()
     }
     fn play_music(&mut self, filename: String) {
         self.queued_commands
@@ -281,6 +800,27 @@ impl Host for GameEntityHost {
                 script_params,
             ))
     }
+    fn spawn_entity(
+        &mut self,
+        prototype_name: String,
+        position: Vector,
+        script_params: Vec<String>,
+    ) -> u64 {
+        let Some(entity) = self.reserved_entities.lock().unwrap().take() else {
+            error!("no reserved entities left this frame, not spawning {prototype_name}");
+            return Entity::PLACEHOLDER.to_bits();
+        };
+
+        self.queued_commands.push(EntityScriptCommand::SpawnEntity {
+            entity,
+            prototype_name,
+            position: Vec2::new(position.x, position.y),
+            script_params,
+        });
+
+        entity.to_bits()
+    }
+
     fn set_bonfire(&mut self, level_index: u32, spawn_name: String) {
         self.queued_commands.push(EntityScriptCommand::SetBonfire {
             level_index,
@@ -294,12 +834,12 @@ impl Host for GameEntityHost {
 }
 
 pub fn scripted_entity_uniform_system(
-    player: Query<(&Transform, &FacingDirection), With<Player>>,
-    mut entities: Query<(&mut EntityScript, &Transform)>,
+    player: Query<(&Transform, &FacingDirection, Has<Parrying>), With<Player>>,
+    mut entities: Query<(&mut EntityScript, &Transform, Option<&FacingDirection>)>,
 ) {
-    let (player_transform, player_direction) = player.single();
+    let (player_transform, player_direction, player_parrying) = player.single();
 
-    for (mut script, transform) in entities.iter_mut() {
+    for (mut script, transform, facing) in entities.iter_mut() {
         let data = script.store.data_mut();
 
         data.host.player_uniform.position = (
@@ -310,54 +850,106 @@ pub fn scripted_entity_uniform_system(
             FacingDirection::West => Direction::West,
             _ => Direction::East,
         };
+        data.host.player_uniform.is_parrying = player_parrying;
 
         data.host.self_uniform.position = (transform.translation.x, transform.translation.y);
+
+        if let Some(facing) = facing {
+            data.host.self_uniform.facing = match facing {
+                FacingDirection::West => Direction::West,
+                _ => Direction::East,
+            };
+        }
+    }
+}
+
+/// Refreshes the scripts' copy of the query pipeline before they run, on every tick while any exist
+pub fn script_spatial_query_sync_system(
+    pipeline: Res<SpatialQueryPipeline>,
+    game_data: Res<GameData>,
+    scripts: Query<(), With<EntityScript>>,
+) {
+    if scripts.is_empty() {
+        return;
+    }
+
+    game_data
+        .spatial_query
+        .pipeline
+        .write()
+        .unwrap()
+        .clone_from(&pipeline);
+}
+
+/// Hands scripts a snapshot of every entity's status effects
+pub fn script_status_effects_sync_system(
+    affected: Query<(Entity, &StatusEffects)>,
+    game_data: Res<GameData>,
+) {
+    let mut status_effects = game_data.status_effects.write().unwrap();
+
+    status_effects.clear();
+    status_effects.extend(
+        affected
+            .iter()
+            .map(|(entity, effects)| (entity, effects.effects())),
+    );
+}
+
+/// Keeps the pool of entities behind `spawn-entity` topped up
+pub fn reserve_script_entities_system(mut commands: Commands, game_data: Res<GameData>) {
+    game_data
+        .reserved_entities
+        .lock()
+        .unwrap()
+        .refill(|| commands.spawn_empty().id());
+}
+
+/// Hands back the entities reserved by spawns a despawned script queued but never got to apply
+pub fn script_reservations_removed_observer(
+    trigger: Trigger<OnRemove, EntityScript>,
+    mut scripts: Query<&mut EntityScript>,
+) {
+    if let Ok(mut script) = scripts.get_mut(trigger.entity()) {
+        script.discard_queued_commands();
     }
 }
 
+/// Ticks the scripts in range of the player, stunned entities skip their ticks
 pub fn tick_scripted_entity_system(
     time: Res<Time>,
     player: Query<&Transform, With<Player>>,
-    mut scripted_entities: Query<(
-        Entity,
-        &mut EntityScript,
-        &TickingEntity,
-        Option<&Transform>,
-    )>,
+    mut scripted_entities: Query<
+        (
+            Entity,
+            &mut EntityScript,
+            &TickingEntity,
+            Option<&Transform>,
+        ),
+        Without<Stunned>,
+    >,
 ) {
     let player = player.single();
     let delta_t = time.elapsed_secs();
 
     for (_entity, mut script, TickingEntity(distance), transform) in scripted_entities.iter_mut() {
-        let EntityScript {
-            game_entity,
-            store,
-            entity_resource,
-        } = script.as_mut();
-        {
-            let in_range = if let Some(distance) = distance {
-                if transform.is_none()
-                    || transform.unwrap().translation.distance(player.translation) > *distance
-                {
-                    false
-                } else {
-                    true
-                }
+        let in_range = if let Some(distance) = distance {
+            if transform.is_none()
+                || transform.unwrap().translation.distance(player.translation) > *distance
+            {
+                false
             } else {
                 true
-            };
-
-            if !in_range {
-                continue;
             }
+        } else {
+            true
+        };
 
-            let guest = game_entity.gamejam_game_entity_resource();
-            let entity_resource_guest = guest.game_entity();
-
-            entity_resource_guest
-                .call_tick(store.as_context_mut(), *entity_resource, delta_t)
-                .unwrap();
+        if !in_range {
+            continue;
         }
+
+        script.tick(delta_t);
     }
 }
 
@@ -376,12 +968,94 @@ pub fn script_interaction_observer(
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScriptEventValue {
+    String(String),
+    Int(i32),
+    Float(f32),
+    Vector(Vec2),
+    Entity(Entity),
+}
+
+/// Payload of a [`ScriptEvent`], mirrors `event-data` in `game_host.wit`
+#[derive(Debug, Clone, PartialEq)]
 pub enum ScriptEventData {
     Trigger(u32),
+    String(String),
+    Int(i32),
+    Float(f32),
+    Vector(Vec2),
+    Entity(Entity),
+    KeyValues(Vec<(String, ScriptEventValue)>),
+}
+
+fn entity_from_script(entity_id: u64) -> Entity {
+    Entity::try_from_bits(entity_id).unwrap_or(Entity::PLACEHOLDER)
+}
+
+impl From<game_host::EventValue> for ScriptEventValue {
+    fn from(value: game_host::EventValue) -> Self {
+        match value {
+            game_host::EventValue::String(value) => ScriptEventValue::String(value),
+            game_host::EventValue::Int(value) => ScriptEventValue::Int(value),
+            game_host::EventValue::Float(value) => ScriptEventValue::Float(value),
+            game_host::EventValue::Vector(v) => ScriptEventValue::Vector(Vec2::new(v.x, v.y)),
+            game_host::EventValue::EntityId(id) => ScriptEventValue::Entity(entity_from_script(id)),
+        }
+    }
+}
+
+impl From<ScriptEventValue> for game_host::EventValue {
+    fn from(value: ScriptEventValue) -> Self {
+        match value {
+            ScriptEventValue::String(value) => game_host::EventValue::String(value),
+            ScriptEventValue::Int(value) => game_host::EventValue::Int(value),
+            ScriptEventValue::Float(value) => game_host::EventValue::Float(value),
+            ScriptEventValue::Vector(v) => game_host::EventValue::Vector(Vector { x: v.x, y: v.y }),
+            ScriptEventValue::Entity(entity) => game_host::EventValue::EntityId(entity.to_bits()),
+        }
+    }
+}
+
+impl From<game_host::EventData> for ScriptEventData {
+    fn from(data: game_host::EventData) -> Self {
+        match data {
+            game_host::EventData::Trigger(id) => ScriptEventData::Trigger(id),
+            game_host::EventData::String(value) => ScriptEventData::String(value),
+            game_host::EventData::Int(value) => ScriptEventData::Int(value),
+            game_host::EventData::Float(value) => ScriptEventData::Float(value),
+            game_host::EventData::Vector(v) => ScriptEventData::Vector(Vec2::new(v.x, v.y)),
+            game_host::EventData::EntityId(id) => ScriptEventData::Entity(entity_from_script(id)),
+            game_host::EventData::KeyValues(values) => ScriptEventData::KeyValues(
+                values
+                    .into_iter()
+                    .map(|(key, value)| (key, value.into()))
+                    .collect(),
+            ),
+        }
+    }
 }
 
-#[derive(Event, Debug)]
+impl From<ScriptEventData> for game_host::EventData {
+    fn from(data: ScriptEventData) -> Self {
+        match data {
+            ScriptEventData::Trigger(id) => game_host::EventData::Trigger(id),
+            ScriptEventData::String(value) => game_host::EventData::String(value),
+            ScriptEventData::Int(value) => game_host::EventData::Int(value),
+            ScriptEventData::Float(value) => game_host::EventData::Float(value),
+            ScriptEventData::Vector(v) => game_host::EventData::Vector(Vector { x: v.x, y: v.y }),
+            ScriptEventData::Entity(entity) => game_host::EventData::EntityId(entity.to_bits()),
+            ScriptEventData::KeyValues(values) => game_host::EventData::KeyValues(
+                values
+                    .into_iter()
+                    .map(|(key, value)| (key, value.into()))
+                    .collect(),
+            ),
+        }
+    }
+}
+
+#[derive(Event, Debug, Clone)]
 pub struct ScriptEvent {
     pub topic: u32,
     pub data: ScriptEventData,
@@ -389,32 +1063,113 @@ pub struct ScriptEvent {
 
 pub fn game_entity_script_event_system(
     mut evt: EventReader<ScriptEvent>,
+    game_data: Res<GameData>,
     mut script_entities_query: Query<&mut EntityScript>,
 ) {
     for evt in evt.read() {
-        for mut entity in script_entities_query.iter_mut() {
-            let EntityScript {
-                game_entity,
-                store,
-                entity_resource,
-            } = entity.as_mut();
-            let guest = game_entity.gamejam_game_entity_resource();
-            let entity_resource_guest = guest.game_entity();
-
-            entity_resource_guest
-                .call_receive_event(
-                    store.as_context_mut(),
-                    *entity_resource,
-                    game_host::Event {
-                        topic: evt.topic,
-                        data: match evt.data {
-                            ScriptEventData::Trigger(event_id) => {
-                                game_host::EventData::Trigger(event_id)
-                            }
-                        },
-                    },
-                )
-                .unwrap();
+        // Collected up front, scripts may (un)subscribe while handling the event
+        let subscribers = game_data
+            .event_subscriptions
+            .lock()
+            .unwrap()
+            .subscribers(evt.topic);
+
+        for entity in subscribers {
+            let Ok(mut script) = script_entities_query.get_mut(entity) else {
+                continue;
+            };
+
+            script.receive_event(game_host::Event {
+                topic: evt.topic,
+                data: evt.data.clone().into(),
+            });
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reserved_entities_pool_grows_after_running_dry() {
+        let mut next_index = 0;
+        let mut reserve = || {
+            next_index += 1;
+            Entity::from_raw(next_index)
+        };
+        let mut reserved_entities = ReservedEntities::default();
+
+        reserved_entities.refill(&mut reserve);
+
+        for _ in 0..RESERVED_ENTITY_POOL_SIZE {
+            assert!(reserved_entities.take().is_some());
+        }
+        assert_eq!(reserved_entities.take(), None);
+
+        reserved_entities.refill(&mut reserve);
+
+        assert_eq!(
+            reserved_entities.available.len(),
+            RESERVED_ENTITY_POOL_SIZE * 2
+        );
+    }
+
+    #[test]
+    fn released_entities_are_handed_out_again() {
+        let mut reserved_entities = ReservedEntities::default();
+        reserved_entities.refill(|| Entity::from_raw(1));
+
+        let entity = reserved_entities.take().unwrap();
+        reserved_entities.release(entity);
+
+        let mut reserved = 0;
+        reserved_entities.refill(|| {
+            reserved += 1;
+            Entity::from_raw(2)
+        });
+
+        assert_eq!(reserved, 0);
+        assert_eq!(reserved_entities.take(), Some(entity));
+    }
+
+    #[test]
+    fn discarded_spawns_hand_back_their_reservations() {
+        let reserved_entities = Arc::new(Mutex::new(ReservedEntities::default()));
+        reserved_entities
+            .lock()
+            .unwrap()
+            .refill(|| Entity::from_raw(1));
+
+        let spawned = reserved_entities.lock().unwrap().take().unwrap();
+        let uniform = || EntityUniform {
+            health: None,
+            is_parrying: false,
+            position: (0., 0.),
+            facing: Direction::West,
+        };
+        let mut host = GameEntityHost {
+            entity: Entity::from_raw(2),
+            queued_commands: vec![EntityScriptCommand::SpawnEntity {
+                entity: spawned,
+                prototype_name: "crate".to_string(),
+                position: Vec2::ZERO,
+                script_params: vec![],
+            }],
+            game_state: Default::default(),
+            state_key: None,
+            transient_state: BTreeMap::new(),
+            player_uniform: uniform(),
+            self_uniform: uniform(),
+            spatial_query: Default::default(),
+            status_effects: Default::default(),
+            event_subscriptions: Default::default(),
+            reserved_entities: reserved_entities.clone(),
+        };
+
+        host.discard_queued_commands();
+
+        assert!(host.queued_commands.is_empty());
+        assert_eq!(reserved_entities.lock().unwrap().take(), Some(spawned));
+    }
+}
@@ -16,12 +16,11 @@ pub fn timer_system(
                 continue;
             }
 
-            if let Some(script) = script.as_deref_mut() {
-                script.timer_callback(timer.timer_name);
-            }
-
+            // Timers with an expiration callback belong to the engine, not to the script
             if let Some(f) = timer.on_expiration.take() {
                 f(&mut commands.entity(entity));
+            } else if let Some(script) = script.as_deref_mut() {
+                script.timer_callback(timer.timer_name);
             }
         }
 
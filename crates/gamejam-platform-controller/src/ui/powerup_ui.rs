@@ -42,6 +42,17 @@ pub fn powerup_widget(
         )
         .expect("failed to open ui_roll_icon");
 
+    let (dash_icon, _) = sprite_collection
+        .create_ui_node_animation_bundle(
+            "ui_dash_icon",
+            "idle",
+            Duration::from_millis(5000),
+            true,
+            false,
+            false,
+        )
+        .expect("failed to open ui_dash_icon");
+
     Stack::<Node>::new()
         .layer(
             El::<ImageNode>::new()
@@ -51,14 +62,15 @@ pub fn powerup_widget(
         )
         .layer(
             El::<Node>::new()
-                .width(Val::Px(96. * 2. - 40.))
+                .width(Val::Px(144. * 2. - 40.))
                 .align(Align::center())
                 .child(Row::<Node>::new().items([
                     icon_container(roll_icon, player_mutables.has_rolling.signal()),
                     icon_container(pogo_icon, player_mutables.has_pogo.signal()),
+                    icon_container(dash_icon, player_mutables.has_dash.signal()),
                 ])),
         )
-        .width(Val::Px(96. * 2.))
+        .width(Val::Px(144. * 2.))
         .height(Val::Px(48. * 2.))
 }
 fn icon_container(
@@ -0,0 +1,324 @@
+use bevy::prelude::*;
+use gamejam_platform_controller::combat::attackable::Attacked;
+use gamejam_platform_controller::combat::combat_components::{Health, HitInvulnerable};
+use gamejam_platform_controller::combat::status_effects::{
+    StatusEffect, StatusEffectKind, StatusEffects, Stunned,
+};
+use gamejam_platform_controller::headless::game_harness::{GameHarness, DEFAULT_LEVEL};
+use gamejam_platform_controller::input_systems::PlayerInputAction;
+use gamejam_platform_controller::movement_systems::movement_components::Dashing;
+use gamejam_platform_controller::player_systems::player_components::{
+    Attacking, Grounded, PowerupDash,
+};
+use gamejam_platform_controller::scripting::scripted_game_entity::EntityScript;
+use gamejam_platform_controller::AttackDirection;
+
+/// Loading compiles every script, which takes a while in debug builds
+const MAX_LOADING_FRAMES: u32 = 5000;
+
+/// The tests below are ignored by default, as they need the level and the installed scripts.
+/// `cargo make test-gameplay` installs the scripts and runs them.
+fn started(mut harness: GameHarness) -> GameHarness {
+    let missing_assets = GameHarness::missing_assets(DEFAULT_LEVEL);
+
+    assert!(
+        missing_assets.is_empty(),
+        "game assets are missing, install them with `cargo make install-scripts`: {missing_assets:?}"
+    );
+
+    harness
+        .run_until_playing(MAX_LOADING_FRAMES)
+        .expect("level failed to load");
+
+    harness
+}
+
+fn playing_harness() -> GameHarness {
+    started(GameHarness::new(DEFAULT_LEVEL))
+}
+
+fn landed_harness() -> GameHarness {
+    let mut harness = playing_harness();
+
+    for _ in 0..256 {
+        if harness.player_has::<Grounded>() {
+            return harness;
+        }
+
+        harness.step();
+    }
+
+    panic!("player never landed after spawning");
+}
+
+#[test]
+#[ignore = "needs the level and the installed scripts, run with `cargo make test-gameplay`"]
+fn player_lands_after_spawning() {
+    let mut harness = landed_harness();
+    let landed_at = harness.player_position();
+
+    harness.run_frames(32, &[]);
+
+    assert!(harness.player_has::<Grounded>());
+    assert!((harness.player_position().y - landed_at.y).abs() < 1.);
+}
+
+#[test]
+#[ignore = "needs the level and the installed scripts, run with `cargo make test-gameplay`"]
+fn player_walks_in_the_input_direction() {
+    let mut harness = landed_harness();
+    let start = harness.player_position();
+
+    harness.run_frames(32, &[PlayerInputAction::Horizontal(Vec2::X)]);
+    let after_right = harness.player_position();
+
+    assert!(after_right.x > start.x);
+
+    harness.run_frames(64, &[PlayerInputAction::Horizontal(Vec2::NEG_X)]);
+
+    assert!(harness.player_position().x < after_right.x);
+}
+
+#[test]
+#[ignore = "needs the level and the installed scripts, run with `cargo make test-gameplay`"]
+fn player_jumps_and_comes_back_down() {
+    let mut harness = landed_harness();
+    let start = harness.player_position();
+
+    harness.step_with(&[PlayerInputAction::JumpStart]);
+    harness.run_frames(8, &[PlayerInputAction::Jump]);
+
+    assert!(harness.player_position().y > start.y);
+    assert!(!harness.player_has::<Grounded>());
+
+    harness.step_with(&[PlayerInputAction::JumpAbort]);
+
+    for _ in 0..256 {
+        if harness.player_has::<Grounded>() {
+            break;
+        }
+
+        harness.step();
+    }
+
+    assert!(harness.player_has::<Grounded>());
+    assert!((harness.player_position().y - start.y).abs() < 1.);
+}
+
+#[test]
+#[ignore = "needs the level and the installed scripts, run with `cargo make test-gameplay`"]
+fn attack_pressed_during_a_swing_is_buffered() {
+    let mut harness = landed_harness();
+
+    harness.step_with(&[PlayerInputAction::Attack(AttackDirection::Sideways)]);
+    assert!(harness.player_has::<Attacking>());
+
+    // Too early for another attack, but inside the buffer window of the one after it
+    harness.run_frames(12, &[]);
+    harness.step_with(&[PlayerInputAction::Attack(AttackDirection::Sideways)]);
+
+    let mut attacks = 1;
+    let mut was_attacking = harness.player_has::<Attacking>();
+
+    for _ in 0..64 {
+        harness.step();
+
+        let attacking = harness.player_has::<Attacking>();
+
+        if attacking && !was_attacking {
+            attacks += 1;
+        }
+
+        was_attacking = attacking;
+    }
+
+    assert_eq!(attacks, 2);
+}
+
+#[test]
+#[ignore = "needs the level and the installed scripts, run with `cargo make test-gameplay`"]
+fn air_dash_is_used_once_per_airtime() {
+    let mut harness = landed_harness();
+    let player = harness.player();
+
+    harness
+        .app
+        .world_mut()
+        .entity_mut(player)
+        .insert(PowerupDash);
+
+    harness.step_with(&[PlayerInputAction::JumpStart]);
+    harness.run_frames(4, &[PlayerInputAction::Jump]);
+
+    let dash_start = harness.player_position();
+
+    harness.step_with(&[PlayerInputAction::Dash]);
+    harness.step();
+    assert!(harness.player_has::<Dashing>());
+
+    for _ in 0..64 {
+        if !harness.player_has::<Dashing>() {
+            break;
+        }
+
+        harness.step();
+    }
+
+    assert!(harness.player_position().x > dash_start.x);
+    assert!(!harness.player_has::<Grounded>());
+
+    harness.step_with(&[PlayerInputAction::Dash]);
+    harness.step();
+    assert!(!harness.player_has::<Dashing>());
+}
+
+#[test]
+#[ignore = "needs the level and the installed scripts, run with `cargo make test-gameplay`"]
+fn stunned_player_cannot_walk_until_the_stun_wears_off() {
+    let mut harness = landed_harness();
+    let player = harness.player();
+
+    let mut status_effects = StatusEffects::default();
+    status_effects.apply(StatusEffect {
+        kind: StatusEffectKind::Stun,
+        duration_millis: 300,
+        magnitude: 0.,
+    });
+    harness
+        .app
+        .world_mut()
+        .entity_mut(player)
+        .insert(status_effects);
+
+    harness.step();
+    assert!(harness.player_has::<Stunned>());
+
+    let start = harness.player_position();
+    harness.run_frames(8, &[PlayerInputAction::Horizontal(Vec2::X)]);
+
+    assert!((harness.player_position().x - start.x).abs() < 1.);
+
+    for _ in 0..256 {
+        if !harness.player_has::<Stunned>() {
+            break;
+        }
+
+        harness.step();
+    }
+
+    assert!(!harness.player_has::<StatusEffects>());
+
+    let recovered = harness.player_position();
+    harness.run_frames(32, &[PlayerInputAction::Horizontal(Vec2::X)]);
+
+    assert!(harness.player_position().x > recovered.x);
+}
+
+fn player_health(harness: &mut GameHarness) -> u32 {
+    let player = harness.player();
+
+    harness.app.world().get::<Health>(player).unwrap().0.current
+}
+
+fn hit_player(harness: &mut GameHarness, attacker: Entity) {
+    let player = harness.player();
+
+    harness.app.world_mut().entity_mut(player).insert(Attacked {
+        attacker: Some(attacker),
+        damage: 5,
+        vector: Vec2::X,
+        ..default()
+    });
+    harness.step();
+}
+
+#[test]
+#[ignore = "needs the level and the installed scripts, run with `cargo make test-gameplay`"]
+fn player_is_invulnerable_for_a_while_after_a_hit() {
+    let mut harness = landed_harness();
+    let attacker = harness.app.world_mut().spawn_empty().id();
+    let other_attacker = harness.app.world_mut().spawn_empty().id();
+    let start_health = player_health(&mut harness);
+
+    hit_player(&mut harness, attacker);
+    assert_eq!(player_health(&mut harness), start_health - 5);
+    assert!(harness.player_has::<HitInvulnerable>());
+
+    hit_player(&mut harness, other_attacker);
+    assert_eq!(player_health(&mut harness), start_health - 5);
+
+    for _ in 0..256 {
+        if !harness.player_has::<HitInvulnerable>() {
+            break;
+        }
+
+        harness.step();
+    }
+
+    // Still on cooldown for the first attacker, but not for anyone else
+    hit_player(&mut harness, attacker);
+    assert_eq!(player_health(&mut harness), start_health - 5);
+
+    hit_player(&mut harness, other_attacker);
+    assert_eq!(player_health(&mut harness), start_health - 10);
+}
+
+#[test]
+#[ignore = "needs the level and the installed scripts, run with `cargo make test-gameplay`"]
+fn level_scripts_run_without_faults() {
+    let mut harness = playing_harness();
+
+    harness.run_frames(128, &[]);
+
+    let world = harness.app.world_mut();
+    let mut scripts = world.query::<&EntityScript>();
+
+    assert!(
+        scripts.iter(world).next().is_some(),
+        "level has no scripted entities"
+    );
+
+    for script in scripts.iter(world) {
+        assert!(
+            !script.suspended && script.restart_count == 0,
+            "script {} faulted",
+            script.display_name()
+        );
+    }
+}
+
+#[test]
+#[ignore = "needs the level and the installed scripts, run with `cargo make test-gameplay`"]
+fn replaying_a_recording_reproduces_the_run() {
+    let replay_path =
+        std::env::temp_dir().join(format!("headless-replay-{}.json", std::process::id()));
+    let mut recording = started(GameHarness::recording(DEFAULT_LEVEL, &replay_path));
+
+    recording.run_frames(32, &[PlayerInputAction::Horizontal(Vec2::X)]);
+    recording.step_with(&[PlayerInputAction::JumpStart]);
+    recording.run_frames(8, &[PlayerInputAction::Jump]);
+    recording.step_with(&[PlayerInputAction::JumpAbort]);
+    recording.run_frames(24, &[PlayerInputAction::Horizontal(Vec2::NEG_X)]);
+    recording.step_with(&[PlayerInputAction::Attack(AttackDirection::Sideways)]);
+    recording.run_frames(16, &[]);
+    recording.flush_recording();
+
+    let end_tick = recording.input_tick();
+    let end_position = recording.player_position();
+
+    let mut replay = started(GameHarness::from_replay(DEFAULT_LEVEL, &replay_path));
+    replay.run_replay(1024).expect("replay did not finish");
+
+    while replay.input_tick() < end_tick {
+        replay.step();
+    }
+
+    std::fs::remove_file(&replay_path).unwrap();
+
+    assert_eq!(replay.input_tick(), end_tick);
+    assert!(
+        replay.player_position().distance(end_position) < 0.01,
+        "replay ended at {}, the recording at {end_position}",
+        replay.player_position()
+    );
+}
@@ -104,7 +104,13 @@ impl GuestGameEntity for EggProjectileScript {
 
     fn receive_event(&self, evt: Event) -> () {}
 
+    fn receive_message(&self, _sender: u64, _payload: EventData) {}
+
     fn receive_entity_event(&self, evt: EntityEvent) -> () {
+        if !matches!(evt, EntityEvent::Killed) {
+            return;
+        }
+
         remove_component("avian2d::dynamics::rigid_body::RigidBody");
         play_animation(
             &self.animation_info.sprite_name,
@@ -112,6 +112,8 @@ impl GuestGameEntity for EggSpitterEntity {
 
     fn receive_event(&self, evt: Event) -> () {}
 
+    fn receive_message(&self, _sender: u64, _payload: EventData) {}
+
     fn receive_entity_event(&self, evt: EntityEvent) -> () {}
 
     fn timer_callback(&self, timer: u32) -> () {
@@ -5,12 +5,18 @@ use game_entity_component::gamejam::game::game_host::*;
 use game_entity_component::{export, exports};
 use script_utils::player_utils::{get_direction_to_player, get_vec_to_player};
 use script_utils::script_parameters::ScriptParams;
+use script_utils::world_utils::{is_ledge_ahead, is_wall_ahead};
 use std::cell::Cell;
 
 const WOUND_UP_ATTACK_DELAY_TIMER: u32 = 3000;
 const ATTACK_COOLDOWN_TIMER: u32 = 3001;
 const TURN_TIMER: u32 = 3002;
 
+/// Bipeds only notice the player in front of them
+const VIEW_CONE_DEGREES: f32 = 180.;
+/// How far ahead patrolling bipeds look for walls and ledges to turn around at
+const PATROL_LOOKAHEAD: f32 = 16.;
+
 export!(EntityWorld);
 
 struct EntityWorld;
@@ -238,33 +244,25 @@ impl BipedEnemy {
     }
 
     fn patrol(&self) {
-        let player_vec = get_vec_to_player();
-        let self_uniform = get_self_uniform();
-
-        let direction_to_player = if player_vec.x.signum() < 0. {
-            Direction::West
-        } else {
-            Direction::East
-        };
-
-        if player_vec.length() < self.stats.aggro_range
-            && direction_to_player == self_uniform.facing
-        {
+        if can_see_player(Some(self.stats.aggro_range), Some(VIEW_CONE_DEGREES)) {
             self.enter_state(BipedEnemyStates::Charging);
             return;
         }
 
         let distance_to_patrol_x = get_self_uniform().position.0 - self.start_uniform.position.0;
+        let patrol_direction = self.patrol_direction.get();
+        let path_blocked = is_wall_ahead(patrol_direction, PATROL_LOOKAHEAD)
+            || is_ledge_ahead(patrol_direction, PATROL_LOOKAHEAD, 40.);
 
-        let dir = match self.patrol_direction.get() {
+        let dir = match patrol_direction {
             Direction::East => {
-                if distance_to_patrol_x > 50. {
+                if distance_to_patrol_x > 50. || path_blocked {
                     self.patrol_direction.set(Direction::West);
                 }
                 (1., 0.)
             }
             _ => {
-                if distance_to_patrol_x < -50. {
+                if distance_to_patrol_x < -50. || path_blocked {
                     self.patrol_direction.set(Direction::East);
                 }
 
@@ -334,6 +332,8 @@ impl GuestGameEntity for BipedEnemy {
 
     fn receive_event(&self, evt: Event) -> () {}
 
+    fn receive_message(&self, _sender: u64, _payload: EventData) {}
+
     fn receive_entity_event(&self, evt: EntityEvent) -> () {
         match evt {
             EntityEvent::Killed => {
@@ -341,6 +341,11 @@ impl GuestGameEntity for BipedEnemy {
                 self.is_dead.set(true);
                 self.enter_state(BipedEnemyStates::Dying);
             }
+            EntityEvent::Parried => {
+                if !self.is_dead.get() {
+                    self.enter_state(BipedEnemyStates::Staggered);
+                }
+            }
         }
     }
 
@@ -376,6 +376,8 @@ impl GuestGameEntity for EasterBunnyBossEntity {
     fn receive_event(&self, evt: Event) -> () {
     }
 
+    fn receive_message(&self, _sender: u64, _payload: EventData) {}
+
     fn receive_entity_event(&self, evt: EntityEvent) -> () {
         match evt {
             EntityEvent::Killed => {
@@ -384,11 +386,16 @@ impl GuestGameEntity for EasterBunnyBossEntity {
                 self.enter_state(BunnyStates::Dying);
 
                 set_game_data_kv_int("door-bunny-dead", 1);
-                publish_event(Event {
+                publish_event(&Event {
                     topic: 1,
                     data: EventData::Trigger(3),
                 });
             }
+            EntityEvent::Parried => {
+                if !self.is_dead.get() {
+                    self.enter_state(BunnyStates::Staggered);
+                }
+            }
         }
     }
 
@@ -13,6 +13,7 @@ struct EntityWorld;
 
 const STAGGERED_TIMER: u32 = 1;
 const ATTACK_TIMER: u32 = 2;
+const AGGRO_RANGE: f32 = 100.;
 
 #[derive(Copy, Clone, Ord, PartialOrd, Eq, PartialEq)]
 enum EnemyState {
@@ -197,7 +198,7 @@ impl GuestGameEntity for SimpleEnemyScript {
         let dir = get_vec_to_player();
         self.timer.set(self.timer.get() + delta_t);
 
-        if dir.length() > 100. && self.state.get() == EnemyState::Patrolling {
+        if self.state.get() == EnemyState::Patrolling && !can_see_player(Some(AGGRO_RANGE), None) {
             let distance_to_patrol_x =
                 get_self_uniform().position.0 - self.start_uniform.position.0;
 
@@ -269,6 +270,8 @@ impl GuestGameEntity for SimpleEnemyScript {
         }
     }
 
+    fn receive_message(&self, _sender: u64, _payload: EventData) {}
+
     fn receive_entity_event(&self, event: EntityEvent) {
         match event {
             EntityEvent::Killed => {
@@ -277,6 +280,7 @@ impl GuestGameEntity for SimpleEnemyScript {
                 play_animation(&self.sprite_name, "death", 1000, Direction::East, false);
                 play_sound_once(&self.death_sound);
             }
+            EntityEvent::Parried => {}
         }
     }
 }
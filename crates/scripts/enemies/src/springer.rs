@@ -194,6 +194,8 @@ impl GuestGameEntity for SpringerEnemy {
 
     fn receive_event(&self, evt: Event) -> () {}
 
+    fn receive_message(&self, _sender: u64, _payload: EventData) {}
+
     fn receive_entity_event(&self, evt: EntityEvent) -> () {
         match evt {
             EntityEvent::Killed => {
@@ -201,6 +203,7 @@ impl GuestGameEntity for SpringerEnemy {
                 self.is_dead.set(true);
                 self.enter_state(SpringerEnemyStates::Dead);
             }
+            EntityEvent::Parried => {}
         }
     }
 
@@ -68,6 +68,8 @@ impl GuestGameEntity for BonfireScript {
 
     fn timer_callback(&self, _timer: u32) -> () {}
 
+    fn receive_message(&self, _sender: u64, _payload: EventData) {}
+
     fn receive_entity_event(&self, _: EntityEvent) {}
 }
 
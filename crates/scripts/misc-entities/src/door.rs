@@ -2,7 +2,7 @@ use game_entity_component::exports::gamejam::game::entity_resource::{EntityEvent
 use script_utils::script_parameters::ScriptParams;
 use std::cell::Cell;
 
-use game_entity_component::gamejam::game::game_host::{get_game_data_kv_int, insert_components, play_animation, remove_component, set_game_data_kv_int, Collider, Direction, EventData, InsertableComponents};
+use game_entity_component::gamejam::game::game_host::{get_game_data_kv_int, get_self_state, insert_components, play_animation, remove_component, set_game_data_kv_int, set_self_state, subscribe, Collider, Direction, EventData, InsertableComponents, StateValue};
 use game_entity_component::*;
 
 struct EntityWorld;
@@ -20,8 +20,8 @@ impl Guest for EntityWorld {
 
 struct DoorScript {
     _self_entity_id: u64,
-    trigger_value: u32,
-    state_variable: String,
+    trigger_values: Vec<u32>,
+    state_variable: Option<String>,
     state: Cell<i32>,
 }
 
@@ -34,10 +34,22 @@ impl DoorScript {
 
         let params = ScriptParams::new(params);
 
-        let trigger_value = params.get_parameter::<u32>("trigger-id").unwrap();
-        let state_variable = params.get_parameter::<String>("state-variable").unwrap();
-
-        let game_state = get_game_data_kv_int(&state_variable).unwrap_or(0);
+        // entities pointing at this one in LDtk trigger its listen channel instead
+        let trigger_values = ["trigger-id", "trigger-listen"]
+            .into_iter()
+            .filter_map(|key| params.get_parameter::<u32>(key))
+            .collect();
+        // levers, powerups and bosses publish their triggers on topic 1
+        subscribe(1);
+        let state_variable = params.get_parameter::<String>("state-variable");
+
+        let game_state = match get_self_state("state") {
+            Some(StateValue::Int(state)) => state,
+            _ => state_variable
+                .as_deref()
+                .and_then(get_game_data_kv_int)
+                .unwrap_or(0),
+        };
 
         if game_state == 0 {
             insert_components(&[
@@ -55,7 +67,7 @@ impl DoorScript {
         }
         Self {
             _self_entity_id: self_entity_id,
-            trigger_value,
+            trigger_values,
             state: Cell::new(game_state),
             state_variable,
         }
@@ -87,18 +99,22 @@ impl GuestGameEntity for DoorScript {
     fn receive_event(&self, evt: Event) -> () {
         match evt.data {
             EventData::Trigger(id) => {
-                if id == self.trigger_value && self.state.get() == 0 {
+                if self.trigger_values.contains(&id) && self.state.get() == 0 {
                     self.state.set(1);
+                    set_self_state("state", &StateValue::Int(1));
                     remove_component("avian2d::dynamics::rigid_body::RigidBody");
                     play_animation("door_1", "opening", 1000, Direction::East, false);
                 }
             }
+            _ => {}
         }
     }
 
     fn timer_callback(&self, _timer: u32) -> () {
     }
 
+    fn receive_message(&self, _sender: u64, _payload: EventData) {}
+
     fn receive_entity_event(&self, _: EntityEvent) {}
 }
 
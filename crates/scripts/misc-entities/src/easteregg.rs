@@ -6,7 +6,7 @@ use game_entity_component::exports::gamejam::game::entity_resource::{
 use std::cell::Cell;
 
 use game_entity_component::gamejam::game::game_host::{
-    Direction, InsertableComponents, Interactable,
+    Direction, EventData, InsertableComponents, Interactable,
     insert_components, play_animation
 };
 use game_entity_component::*;
@@ -60,6 +60,8 @@ impl GuestGameEntity for EasterEggScript {
 
     fn timer_callback(&self, _timer: u32) -> () {}
 
+    fn receive_message(&self, _sender: u64, _payload: EventData) {}
+
     fn receive_entity_event(&self, _: EntityEvent) {}
 }
 
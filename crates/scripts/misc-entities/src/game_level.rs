@@ -8,7 +8,7 @@ use game_entity_component::*;
 struct EntityWorld;
 
 use game_entity_component::exports;
-use game_entity_component::gamejam::game::game_host::{get_game_data_kv_int, set_game_data_kv_int};
+use game_entity_component::gamejam::game::game_host::{EventData, get_game_data_kv_int, set_game_data_kv_int};
 
 export!(EntityWorld);
 
@@ -61,6 +61,8 @@ impl GuestGameEntity for GameLevelScript {
 
     fn timer_callback(&self, _timer: u32) -> () {}
 
+    fn receive_message(&self, _sender: u64, _payload: EventData) {}
+
     fn receive_entity_event(&self, _: EntityEvent) {}
 }
 
@@ -3,7 +3,7 @@ use game_entity_component::exports::gamejam::game::entity_resource::{EntityEvent
 use script_utils::script_parameters::ScriptParams;
 use std::cell::Cell;
 use std::time::Duration;
-use game_entity_component::gamejam::game::game_host::{Collider, EventData, InsertableComponents, insert_components, play_animation, publish_event, set_game_data_kv_int, get_game_data_kv_int, remove_component, request_timer_callback, Direction};
+use game_entity_component::gamejam::game::game_host::{Collider, EventData, InsertableComponents, StateValue, insert_components, play_animation, publish_event, set_game_data_kv_int, get_game_data_kv_int, get_self_state, set_self_state, remove_component, request_timer_callback, Direction};
 use game_entity_component::*;
 
 struct EntityWorld;
@@ -22,7 +22,8 @@ impl Guest for EntityWorld {
 struct LeverScript {
     _self_entity_id: u64,
     trigger_targets: Vec<u32>,
-    state_variable: String,
+    /// Shared game data variable other entities read the lever state from
+    state_variable: Option<String>,
     state: Cell<i32>,
     delay: Option<Duration>,
 }
@@ -37,9 +38,15 @@ impl LeverScript {
         let params = ScriptParams::new(params);
 
         let trigger_targets = params.get_list_parameter::<u32>("trigger-targets").unwrap();
-        let state_variable = params.get_parameter::<String>("state-variable").unwrap();
+        let state_variable = params.get_parameter::<String>("state-variable");
         let delay_millis = params.get_parameter::<u32>("delay-millis");
-        let game_state = get_game_data_kv_int(&state_variable).unwrap_or(0);
+        let game_state = match get_self_state("state") {
+            Some(StateValue::Int(state)) => state,
+            _ => state_variable
+                .as_deref()
+                .and_then(get_game_data_kv_int)
+                .unwrap_or(0),
+        };
 
 
         insert_components(&[
@@ -103,6 +110,8 @@ impl GuestGameEntity for LeverScript {
         self.activate();
     }
 
+    fn receive_message(&self, _sender: u64, _payload: EventData) {}
+
     fn receive_entity_event(&self, _: EntityEvent) {}
 }
 
@@ -115,7 +124,12 @@ impl LeverScript {
 
         if self.state.get() == 0 {
             self.state.set(1);
-            set_game_data_kv_int(&self.state_variable, 1);
+            set_self_state("state", &StateValue::Int(1));
+
+            if let Some(state_variable) = &self.state_variable {
+                set_game_data_kv_int(state_variable, 1);
+            }
+
             play_sound_once("audio/lever.wav");
             play_animation("lever", "closing", 1000, Direction::East, false);
         } else if self.state.get() == 1 {
@@ -123,7 +137,7 @@ impl LeverScript {
         }
 
         for val in &self.trigger_targets {
-            publish_event(Event {
+            publish_event(&Event {
                 topic: 1,
                 data: EventData::Trigger(*val),
             });
@@ -6,7 +6,7 @@ use std::cell::Cell;
 
 use game_entity_component::gamejam::game::game_host::{
     Collider, Direction, EventData, InsertableComponents, get_game_data_kv_int, insert_components,
-    play_animation, remove_component, set_game_data_kv_int,
+    play_animation, remove_component, set_game_data_kv_int, subscribe,
 };
 use game_entity_component::*;
 
@@ -25,7 +25,7 @@ impl Guest for EntityWorld {
 
 struct PlatformScript {
     _self_entity_id: u64,
-    trigger_value: u32,
+    trigger_values: Vec<u32>,
     state_variable: String,
     sprite: String,
     width: f32,
@@ -41,7 +41,13 @@ impl PlatformScript {
 
         let params = ScriptParams::new(params);
 
-        let trigger_value = params.get_parameter::<u32>("trigger-id").unwrap();
+        // entities pointing at this one in LDtk trigger its listen channel instead
+        let trigger_values = ["trigger-id", "trigger-listen"]
+            .into_iter()
+            .filter_map(|key| params.get_parameter::<u32>(key))
+            .collect();
+        // levers, powerups and bosses publish their triggers on topic 1
+        subscribe(1);
         let sprite = params.get_parameter::<String>("sprite-name").unwrap();
         let state_variable = params.get_parameter::<String>("state-variable").unwrap();
         let width = params.get_parameter::<f32>("platform-width").unwrap_or(64.);
@@ -49,7 +55,7 @@ impl PlatformScript {
 
         let out = Self {
             _self_entity_id: self_entity_id,
-            trigger_value,
+            trigger_values,
             state_variable,
             sprite,
             width,
@@ -106,7 +112,7 @@ impl GuestGameEntity for PlatformScript {
     fn receive_event(&self, evt: Event) -> () {
         match evt.data {
             EventData::Trigger(id) => {
-                if id == self.trigger_value {
+                if self.trigger_values.contains(&id) {
                     if self.is_solid() {
                         insert_components(&[InsertableComponents::Collider(Collider {
                             width: self.width,
@@ -121,11 +127,14 @@ impl GuestGameEntity for PlatformScript {
                     }
                 }
             }
+            _ => {}
         }
     }
 
     fn timer_callback(&self, _timer: u32) -> () {}
 
+    fn receive_message(&self, _sender: u64, _payload: EventData) {}
+
     fn receive_entity_event(&self, _: EntityEvent) {}
 }
 
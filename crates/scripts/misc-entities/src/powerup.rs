@@ -10,9 +10,9 @@ struct EntityWorld;
 
 use game_entity_component::exports;
 use game_entity_component::gamejam::game::game_host::{
-    Direction, EventData, InsertableComponents, Interactable, despawn_entity, get_game_data_kv_int,
-    insert_components, play_animation, play_sound_once, publish_event, request_timer_callback,
-    set_game_data_kv_int,
+    Direction, EventData, InsertableComponents, Interactable, StateValue, despawn_entity,
+    get_game_data_kv_int, get_self_state, insert_components, play_animation, play_sound_once,
+    publish_event, request_timer_callback, set_game_data_kv_int, set_self_state,
 };
 
 export!(EntityWorld);
@@ -28,7 +28,7 @@ impl Guest for EntityWorld {
 struct PowerupScript {
     self_entity_id: u64,
     power: String,
-    state_variable: String,
+    state_variable: Option<String>,
     trigger_id: Option<u32>,
 }
 
@@ -43,12 +43,21 @@ impl PowerupScript {
         let pickup_text = params.get_parameter::<String>("label");
         let sprite_name = params.get_parameter::<String>("sprite-name").unwrap();
         let power = params.get_parameter::<String>("powerup").unwrap();
-        let state_variable = params.get_parameter::<String>("state-variable").unwrap();
+        let state_variable = params.get_parameter::<String>("state-variable");
         let trigger_id = params.get_parameter::<u32>("trigger-id");
 
         play_animation(&sprite_name, "idle", 1000, Direction::East, true);
 
-        if get_game_data_kv_int(&state_variable).unwrap_or(0) == 1 {
+        let collected = match get_self_state("collected") {
+            Some(StateValue::Bool(collected)) => collected,
+            _ => state_variable
+                .as_deref()
+                .and_then(get_game_data_kv_int)
+                .unwrap_or(0)
+                == 1,
+        };
+
+        if collected {
             despawn_entity(self_entity_id);
         } else {
             insert_components(&[InsertableComponents::Interactable(Interactable {
@@ -72,11 +81,16 @@ impl GuestGameEntity for PowerupScript {
     fn interacted(&self) -> () {
         play_sound_once("audio/lvlup.ogg");
         grant_player_power(&self.power);
-        set_game_data_kv_int(&self.state_variable, 1);
+        set_self_state("collected", &StateValue::Bool(true));
+
+        if let Some(state_variable) = &self.state_variable {
+            set_game_data_kv_int(state_variable, 1);
+        }
+
         request_timer_callback(0, 100);
 
         if let Some(val) = self.trigger_id {
-            publish_event(Event {
+            publish_event(&Event {
                 topic: 1,
                 data: EventData::Trigger(val),
             });
@@ -93,6 +107,8 @@ impl GuestGameEntity for PowerupScript {
         despawn_entity(self.self_entity_id);
     }
 
+    fn receive_message(&self, _sender: u64, _payload: EventData) {}
+
     fn receive_entity_event(&self, _: EntityEvent) {}
 }
 
@@ -1,2 +1,3 @@
 pub mod script_parameters;
 pub mod player_utils;
+pub mod world_utils;
@@ -0,0 +1,43 @@
+use game_entity_component::gamejam::game::game_host::*;
+
+pub const PLAYER_LAYER: u32 = 0b00001;
+pub const WALL_LAYER: u32 = 0b00100;
+pub const ENEMY_LAYER: u32 = 0b01000;
+
+fn direction_x(direction: Direction) -> f32 {
+    match direction {
+        Direction::West => -1.,
+        Direction::East => 1.,
+    }
+}
+
+pub fn is_wall_ahead(direction: Direction, distance: f32) -> bool {
+    let (x, y) = get_self_uniform().position;
+
+    raycast(
+        Vector { x, y },
+        Vector {
+            x: direction_x(direction),
+            y: 0.,
+        },
+        distance,
+        WALL_LAYER,
+    )
+    .is_some()
+}
+
+/// Checks for missing ground `lookahead` units in front of the entity
+pub fn is_ledge_ahead(direction: Direction, lookahead: f32, depth: f32) -> bool {
+    let (x, y) = get_self_uniform().position;
+
+    raycast(
+        Vector {
+            x: x + direction_x(direction) * lookahead,
+            y,
+        },
+        Vector { x: 0., y: -1. },
+        depth,
+        WALL_LAYER,
+    )
+    .is_none()
+}